@@ -23,6 +23,27 @@ fn main() {
     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
     let libdir = std::path::PathBuf::from(String::from_utf8(output).unwrap().trim());
 
+    // Build C++ helpers for functionality missing from the C API
+    let cxxflags = std::process::Command::new(std::env::var("LLVM_CONFIG").unwrap_or_else(|_| {
+        std::env::var("DEP_LLVM_CONFIG_PATH").unwrap_or_else(|_| "llvm-config".to_string())
+    }))
+    .arg("--cxxflags")
+    .output()
+    .unwrap()
+    .stdout;
+    let mut build = cc::Build::new();
+    build
+        .cpp(true)
+        .warnings(false)
+        .file("src/data_array.cpp")
+        .file("src/call.cpp");
+    for flag in String::from_utf8(cxxflags).unwrap().split_whitespace() {
+        build.flag(flag);
+    }
+    build.compile("llama_cpp");
+    println!("cargo:rerun-if-changed=src/data_array.cpp");
+    println!("cargo:rerun-if-changed=src/call.cpp");
+
     // Copy LTO lib
     let lto_file_name = format!("libLTO.{}", shared_lib);
     let lto = libdir.join(&lto_file_name);
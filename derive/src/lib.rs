@@ -0,0 +1,145 @@
+#![deny(missing_docs)]
+
+//! `llama-derive` provides `#[derive(LLVMType)]` for `#[repr(C)]` structs
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+
+fn repr(input: &syn::DeriveInput) -> syn::Result<(bool, bool)> {
+    let mut c = false;
+    let mut packed = false;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                c = true;
+            } else if meta.path.is_ident("packed") {
+                packed = true;
+                if meta.input.peek(syn::token::Paren) {
+                    return Err(meta.error("packed(N) is not supported"));
+                }
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let _: proc_macro2::TokenStream = content.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok((c, packed))
+}
+
+fn expand(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "LLVMType cannot be derived for generic structs",
+        ));
+    }
+
+    let fields = match &input.data {
+        syn::Data::Struct(s) => &s.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "LLVMType can only be derived for structs",
+            ))
+        }
+    };
+
+    let (c, packed) = repr(&input)?;
+    if !c {
+        return Err(syn::Error::new_spanned(
+            name,
+            "LLVMType can only be derived for #[repr(C)] structs",
+        ));
+    }
+
+    let types: Vec<&syn::Type> = fields.iter().map(|f| &f.ty).collect();
+    let members: Vec<syn::Member> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        })
+        .collect();
+    let index = 0..members.len();
+
+    let new = if packed {
+        quote!(new_packed)
+    } else {
+        quote!(new)
+    };
+
+    let test_name = format_ident!("__llama_derive_layout_{}", name);
+
+    Ok(quote! {
+        impl<'a> llama::LLVMType<'a> for #name {
+            fn llvm_type(ctx: &llama::Context<'a>) -> Result<llama::Type<'a>, llama::Error> {
+                let fields: Vec<llama::Type<'a>> = vec![
+                    #(<#types as llama::LLVMType<'a>>::llvm_type(ctx)?),*
+                ];
+                Ok(llama::StructType::#new(ctx, fields)?.into())
+            }
+
+            fn llvm_target_type(
+                ctx: &llama::Context<'a>,
+                data: &llama::TargetData<'a>,
+            ) -> Result<llama::Type<'a>, llama::Error> {
+                let fields: Vec<llama::Type<'a>> = vec![
+                    #(<#types as llama::LLVMType<'a>>::llvm_target_type(ctx, data)?),*
+                ];
+                Ok(llama::StructType::#new(ctx, fields)?.into())
+            }
+        }
+
+        #[cfg(test)]
+        #[test]
+        #[allow(non_snake_case)]
+        fn #test_name() -> Result<(), llama::Error> {
+            let ctx = llama::Context::new()?;
+            let t = <#name as llama::LLVMType>::llvm_type(&ctx)?;
+            let target = llama::Target::default()?;
+            let machine = llama::TargetMachine::new(
+                &target,
+                llama::default_target_triple(),
+                "",
+                "",
+                llama::CodeGenOptLevel::LLVMCodeGenLevelDefault,
+                llama::RelocMode::LLVMRelocDefault,
+                llama::CodeModel::LLVMCodeModelDefault,
+            )?;
+            let data = machine.data_layout()?;
+            #(
+                assert_eq!(
+                    data.struct_offset_of_element(t, #index),
+                    std::mem::offset_of!(#name, #members),
+                    concat!("offset of ", stringify!(#name), ".", stringify!(#members)),
+                );
+            )*
+            assert_eq!(
+                data.abi_size_of_type(t),
+                std::mem::size_of::<#name>(),
+                concat!("size of ", stringify!(#name)),
+            );
+            Ok(())
+        }
+    })
+}
+
+/// Derive `llama::LLVMType` for a `#[repr(C)]` struct
+///
+/// This also generates a test that compares the field offsets computed by LLVM for the host
+/// target with the offsets computed by `rustc`
+#[proc_macro_derive(LLVMType)]
+pub fn derive_llvm_type(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    match expand(input) {
+        Ok(x) => x.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,48 @@
+use llama::*;
+use llama_derive::LLVMType;
+
+#[derive(LLVMType)]
+#[repr(C)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[derive(LLVMType)]
+#[repr(C)]
+struct Mixed {
+    a: u8,
+    b: f64,
+    c: *const Point,
+    d: i16,
+}
+
+#[derive(LLVMType)]
+#[repr(C, packed)]
+struct Packed {
+    a: u8,
+    b: u32,
+}
+
+#[derive(LLVMType)]
+#[repr(C)]
+struct Tuple(u8, u64);
+
+#[test]
+fn struct_type() -> Result<(), Error> {
+    let ctx = Context::new()?;
+
+    let t = Type::of::<Point>(&ctx)?;
+    assert_eq!(t.to_string(), "{ float, float }");
+
+    let t = Type::of::<Mixed>(&ctx)?;
+    assert_eq!(t.to_string(), "{ i8, double, { float, float }*, i16 }");
+
+    let t = Type::of::<Packed>(&ctx)?;
+    assert!(t.to_struct_type()?.is_packed());
+
+    let t = Type::of::<Tuple>(&ctx)?;
+    assert_eq!(t.to_struct_type()?.field_count(), 2);
+
+    Ok(())
+}
@@ -1,6 +1,264 @@
 use crate::*;
 
+/// Enum attribute kinds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttrKind {
+    /// `alwaysinline`
+    AlwaysInline,
+    /// `argmemonly`
+    ArgMemOnly,
+    /// `cold`
+    Cold,
+    /// `convergent`
+    Convergent,
+    /// `hot`
+    Hot,
+    /// `inaccessiblememonly`
+    InaccessibleMemOnly,
+    /// `inlinehint`
+    InlineHint,
+    /// `inreg`
+    InReg,
+    /// `minsize`
+    MinSize,
+    /// `naked`
+    Naked,
+    /// `nest`
+    Nest,
+    /// `noalias`
+    NoAlias,
+    /// `nocapture`
+    NoCapture,
+    /// `nofree`
+    NoFree,
+    /// `noinline`
+    NoInline,
+    /// `nonnull`
+    NonNull,
+    /// `norecurse`
+    NoRecurse,
+    /// `noreturn`
+    NoReturn,
+    /// `nosync`
+    NoSync,
+    /// `noundef`
+    NoUndef,
+    /// `nounwind`
+    NoUnwind,
+    /// `optnone`
+    OptimizeNone,
+    /// `optsize`
+    OptimizeForSize,
+    /// `readnone`
+    ReadNone,
+    /// `readonly`
+    ReadOnly,
+    /// `returned`
+    Returned,
+    /// `signext`
+    SExt,
+    /// `speculatable`
+    Speculatable,
+    /// `ssp`
+    StackProtect,
+    /// `sspstrong`
+    StackProtectStrong,
+    /// `uwtable`
+    UWTable,
+    /// `willreturn`
+    WillReturn,
+    /// `writeonly`
+    WriteOnly,
+    /// `zeroext`
+    ZExt,
+    /// `align(n)`, alignment in bytes
+    Align(u64),
+    /// `alignstack(n)`, alignment in bytes
+    StackAlignment(u64),
+    /// `dereferenceable(n)`
+    Dereferenceable(u64),
+    /// `dereferenceable_or_null(n)`
+    DereferenceableOrNull(u64),
+}
+
+impl AttrKind {
+    /// Get the name of the attribute as used in LLVM IR
+    pub fn name(self) -> &'static str {
+        use AttrKind::*;
+        match self {
+            AlwaysInline => "alwaysinline",
+            ArgMemOnly => "argmemonly",
+            Cold => "cold",
+            Convergent => "convergent",
+            Hot => "hot",
+            InaccessibleMemOnly => "inaccessiblememonly",
+            InlineHint => "inlinehint",
+            InReg => "inreg",
+            MinSize => "minsize",
+            Naked => "naked",
+            Nest => "nest",
+            NoAlias => "noalias",
+            NoCapture => "nocapture",
+            NoFree => "nofree",
+            NoInline => "noinline",
+            NonNull => "nonnull",
+            NoRecurse => "norecurse",
+            NoReturn => "noreturn",
+            NoSync => "nosync",
+            NoUndef => "noundef",
+            NoUnwind => "nounwind",
+            OptimizeNone => "optnone",
+            OptimizeForSize => "optsize",
+            ReadNone => "readnone",
+            ReadOnly => "readonly",
+            Returned => "returned",
+            SExt => "signext",
+            Speculatable => "speculatable",
+            StackProtect => "ssp",
+            StackProtectStrong => "sspstrong",
+            UWTable => "uwtable",
+            WillReturn => "willreturn",
+            WriteOnly => "writeonly",
+            ZExt => "zeroext",
+            Align(_) => "align",
+            StackAlignment(_) => "alignstack",
+            Dereferenceable(_) => "dereferenceable",
+            DereferenceableOrNull(_) => "dereferenceable_or_null",
+        }
+    }
+
+    /// Get the value associated with the attribute, this is 0 for attributes without a value
+    pub fn value(self) -> u64 {
+        match self {
+            AttrKind::Align(n)
+            | AttrKind::StackAlignment(n)
+            | AttrKind::Dereferenceable(n)
+            | AttrKind::DereferenceableOrNull(n) => n,
+            _ => 0,
+        }
+    }
+
+    /// Get the kind ID used by LLVM
+    pub fn id(self) -> u32 {
+        let name = self.name();
+        unsafe {
+            llvm::core::LLVMGetEnumAttributeKindForName(name.as_ptr() as *const c_char, name.len())
+        }
+    }
+}
+
+macro_rules! attr {
+    ($($(#[$meta:meta])* $name:ident: $kind:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(ctx: &Context<'a>) -> Result<Attribute<'a>, Error> {
+                Attribute::new(ctx, AttrKind::$kind)
+            }
+        )*
+    };
+}
+
+/// Names of all enum and type attributes known to LLVM 14
+const ATTRIBUTE_NAMES: &[&str] = &[
+    "align",
+    "allocsize",
+    "alwaysinline",
+    "argmemonly",
+    "builtin",
+    "byref",
+    "byval",
+    "cold",
+    "convergent",
+    "dereferenceable",
+    "dereferenceable_or_null",
+    "disable_sanitizer_instrumentation",
+    "elementtype",
+    "hot",
+    "immarg",
+    "inaccessiblemem_or_argmemonly",
+    "inaccessiblememonly",
+    "inalloca",
+    "inlinehint",
+    "inreg",
+    "jumptable",
+    "minsize",
+    "mustprogress",
+    "naked",
+    "nest",
+    "noalias",
+    "nobuiltin",
+    "nocallback",
+    "nocapture",
+    "nocf_check",
+    "noduplicate",
+    "nofree",
+    "noimplicitfloat",
+    "noinline",
+    "nomerge",
+    "nonlazybind",
+    "nonnull",
+    "noprofile",
+    "noredzone",
+    "norecurse",
+    "noreturn",
+    "nosanitize_coverage",
+    "nosync",
+    "noundef",
+    "nounwind",
+    "null_pointer_is_valid",
+    "optforfuzzing",
+    "optnone",
+    "optsize",
+    "preallocated",
+    "readnone",
+    "readonly",
+    "returned",
+    "returns_twice",
+    "safestack",
+    "sanitize_address",
+    "sanitize_hwaddress",
+    "sanitize_memory",
+    "sanitize_memtag",
+    "sanitize_thread",
+    "shadowcallstack",
+    "signext",
+    "speculatable",
+    "speculative_load_hardening",
+    "sret",
+    "ssp",
+    "sspreq",
+    "sspstrong",
+    "strictfp",
+    "swiftasync",
+    "swifterror",
+    "swiftself",
+    "uwtable",
+    "vscale_range",
+    "willreturn",
+    "writeonly",
+    "zeroext",
+    "alignstack",
+];
+
+lazy_static::lazy_static! {
+    /// Attribute kind IDs mapped to the names in `ATTRIBUTE_NAMES`, used to print attributes
+    pub(crate) static ref ATTRIBUTE_KINDS: std::collections::HashMap<u32, &'static str> =
+        ATTRIBUTE_NAMES
+            .iter()
+            .map(|name| {
+                let kind = unsafe {
+                    llvm::core::LLVMGetEnumAttributeKindForName(
+                        name.as_ptr() as *const c_char,
+                        name.len(),
+                    )
+                };
+                (kind, *name)
+            })
+            .collect();
+}
+
 /// LLVM Attribute
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct Attribute<'a>(NonNull<llvm::LLVMOpaqueAttributeRef>, PhantomData<&'a ()>);
 
 llvm_inner_impl!(Attribute<'a>, llvm::LLVMOpaqueAttributeRef);
@@ -34,6 +292,105 @@ impl<'a> Attribute<'a> {
         unsafe { Attribute::from_inner(llvm::core::LLVMCreateEnumAttribute(ctx.llvm(), k, v)) }
     }
 
+    /// Create an enum attribute from an `AttrKind`, alignments must be a power of two
+    pub fn new(ctx: &Context<'a>, kind: AttrKind) -> Result<Attribute<'a>, Error> {
+        if let AttrKind::Align(n) | AttrKind::StackAlignment(n) = kind {
+            if !n.is_power_of_two() {
+                return Err(Error::InvalidAttribute(format!("{}({})", kind.name(), n)));
+            }
+        }
+        let id = kind.id();
+        if id == 0 {
+            return Err(Error::InvalidAttribute(kind.name().to_string()));
+        }
+        Attribute::new_enum(ctx, id, kind.value())
+    }
+
+    attr!(
+        /// `alwaysinline` function attribute
+        always_inline: AlwaysInline,
+        /// `cold` function attribute
+        cold: Cold,
+        /// `inlinehint` function attribute
+        inline_hint: InlineHint,
+        /// `noalias` parameter or return attribute
+        no_alias: NoAlias,
+        /// `nocapture` parameter attribute
+        no_capture: NoCapture,
+        /// `noinline` function attribute
+        no_inline: NoInline,
+        /// `nonnull` parameter or return attribute
+        non_null: NonNull,
+        /// `noreturn` function attribute
+        no_return: NoReturn,
+        /// `nounwind` function attribute
+        no_unwind: NoUnwind,
+        /// `readnone` function or parameter attribute
+        read_none: ReadNone,
+        /// `readonly` function or parameter attribute
+        read_only: ReadOnly,
+        /// `writeonly` function or parameter attribute
+        write_only: WriteOnly,
+        /// `willreturn` function attribute
+        will_return: WillReturn,
+    );
+
+    /// `align(n)` parameter or return attribute
+    pub fn align(ctx: &Context<'a>, n: u64) -> Result<Attribute<'a>, Error> {
+        Attribute::new(ctx, AttrKind::Align(n))
+    }
+
+    /// `alignstack(n)` function or parameter attribute
+    pub fn stack_alignment(ctx: &Context<'a>, n: u64) -> Result<Attribute<'a>, Error> {
+        Attribute::new(ctx, AttrKind::StackAlignment(n))
+    }
+
+    /// `dereferenceable(n)` parameter or return attribute
+    pub fn dereferenceable(ctx: &Context<'a>, n: u64) -> Result<Attribute<'a>, Error> {
+        Attribute::new(ctx, AttrKind::Dereferenceable(n))
+    }
+
+    /// Create a type attribute, `name` is one of `byval`, `sret`, `elementtype`, `inalloca` or
+    /// `preallocated`
+    pub fn new_type(
+        ctx: &Context<'a>,
+        name: impl AsRef<str>,
+        t: impl AsRef<Type<'a>>,
+    ) -> Result<Attribute<'a>, Error> {
+        let name = name.as_ref();
+        let id = unsafe {
+            llvm::core::LLVMGetEnumAttributeKindForName(name.as_ptr() as *const c_char, name.len())
+        };
+        if id == 0 {
+            return Err(Error::InvalidAttribute(name.to_string()));
+        }
+        unsafe {
+            Attribute::from_inner(llvm::core::LLVMCreateTypeAttribute(
+                ctx.llvm(),
+                id,
+                t.as_ref().llvm(),
+            ))
+        }
+    }
+
+    /// `sret(t)` parameter attribute
+    pub fn sret(t: impl AsRef<Type<'a>>) -> Result<Attribute<'a>, Error> {
+        let t = t.as_ref();
+        Attribute::new_type(&t.into_context()?, "sret", t)
+    }
+
+    /// `byval(t)` parameter attribute
+    pub fn byval(t: impl AsRef<Type<'a>>) -> Result<Attribute<'a>, Error> {
+        let t = t.as_ref();
+        Attribute::new_type(&t.into_context()?, "byval", t)
+    }
+
+    /// `elementtype(t)` parameter attribute
+    pub fn element_type(t: impl AsRef<Type<'a>>) -> Result<Attribute<'a>, Error> {
+        let t = t.as_ref();
+        Attribute::new_type(&t.into_context()?, "elementtype", t)
+    }
+
     /// Returns true when the attribute is an enum
     pub fn is_enum(&self) -> bool {
         unsafe { llvm::core::LLVMIsEnumAttribute(self.llvm()) == 1 }
@@ -44,6 +401,19 @@ impl<'a> Attribute<'a> {
         unsafe { llvm::core::LLVMIsStringAttribute(self.llvm()) == 1 }
     }
 
+    /// Returns true when the attribute is a type attribute
+    pub fn is_type(&self) -> bool {
+        unsafe { llvm::core::LLVMIsTypeAttribute(self.llvm()) == 1 }
+    }
+
+    /// Get type attribute value
+    pub fn type_value(&self) -> Option<Type<'a>> {
+        if !self.is_type() {
+            return None;
+        }
+        unsafe { Type::from_inner(llvm::core::LLVMGetTypeAttributeValue(self.llvm())).ok() }
+    }
+
     /// Get string attribute kind
     pub fn string_kind(&self) -> Option<&str> {
         if !self.is_string() {
@@ -53,10 +423,7 @@ impl<'a> Attribute<'a> {
         let mut len = 0;
         let ptr = unsafe { llvm::core::LLVMGetStringAttributeKind(self.llvm(), &mut len) };
         let slice = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
-        match std::str::from_utf8(slice) {
-            Ok(x) => Some(x),
-            Err(_) => None,
-        }
+        std::str::from_utf8(slice).ok()
     }
 
     /// Get string attribute value
@@ -68,10 +435,7 @@ impl<'a> Attribute<'a> {
         let mut len = 0;
         let ptr = unsafe { llvm::core::LLVMGetStringAttributeValue(self.llvm(), &mut len) };
         let slice = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
-        match std::str::from_utf8(slice) {
-            Ok(x) => Some(x),
-            Err(_) => None,
-        }
+        std::str::from_utf8(slice).ok()
     }
 
     /// Get enum attribute kind
@@ -90,3 +454,45 @@ impl<'a> Attribute<'a> {
         unsafe { Some(llvm::core::LLVMGetEnumAttributeValue(self.llvm())) }
     }
 }
+
+impl<'a> Attribute<'a> {
+    fn enum_name(&self) -> Option<&'static str> {
+        let kind = unsafe { llvm::core::LLVMGetEnumAttributeKind(self.llvm()) };
+        ATTRIBUTE_KINDS.get(&kind).copied()
+    }
+}
+
+impl<'a> std::fmt::Display for Attribute<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_string() {
+            let k = self.string_kind().unwrap_or_default();
+            return match self.string_value() {
+                Some(v) if !v.is_empty() => {
+                    write!(fmt, "\"{}\"=\"{}\"", k.escape_default(), v.escape_default())
+                }
+                _ => write!(fmt, "\"{}\"", k.escape_default()),
+            };
+        }
+
+        let kind = unsafe { llvm::core::LLVMGetEnumAttributeKind(self.llvm()) };
+        match self.enum_name() {
+            Some(name) => write!(fmt, "{}", name)?,
+            None => write!(fmt, "#{}", kind)?,
+        }
+
+        if let Some(t) = self.type_value() {
+            write!(fmt, "({})", t)
+        } else {
+            match self.enum_value() {
+                Some(v) if v != 0 => write!(fmt, "({})", v),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+impl<'a> std::fmt::Debug for Attribute<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self)
+    }
+}
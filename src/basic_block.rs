@@ -1,17 +1,11 @@
 use crate::*;
 
 /// BasicBlock wraps LLVMBasicBlock
-#[derive(Copy)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct BasicBlock<'a>(NonNull<llvm::LLVMBasicBlock>, PhantomData<&'a ()>);
 
 llvm_inner_impl!(BasicBlock<'a>, llvm::LLVMBasicBlock);
 
-impl<'a> Clone for BasicBlock<'a> {
-    fn clone(&self) -> BasicBlock<'a> {
-        BasicBlock(self.0, PhantomData)
-    }
-}
-
 impl<'a> BasicBlock<'a> {
     /// Wrap a raw pointer
     pub fn from_inner(ptr: *mut llvm::LLVMBasicBlock) -> Result<Self, Error> {
@@ -95,6 +89,24 @@ impl<'a> BasicBlock<'a> {
         }
     }
 
+    /// Get all predecessor blocks, a block is listed once for each edge
+    pub fn predecessors(self) -> Vec<BasicBlock<'a>> {
+        let mut preds = Vec::new();
+        unsafe {
+            let mut u = llvm::core::LLVMGetFirstUse(llvm::core::LLVMBasicBlockAsValue(self.llvm()));
+            while !u.is_null() {
+                let user = llvm::core::LLVMGetUser(u);
+                if !llvm::core::LLVMIsATerminatorInst(user).is_null() {
+                    if let Ok(bb) = Self::from_inner(llvm::core::LLVMGetInstructionParent(user)) {
+                        preds.push(bb);
+                    }
+                }
+                u = llvm::core::LLVMGetNextUse(u);
+            }
+        }
+        preds
+    }
+
     /// Get the first instruction in a basic block
     pub fn first_instruction(self) -> Result<Value<'a>, Error> {
         unsafe {
@@ -127,3 +139,20 @@ impl<'a> BasicBlock<'a> {
         }
     }
 }
+
+impl<'a> std::fmt::Display for BasicBlock<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = unsafe {
+            Message::from_raw(llvm::core::LLVMPrintValueToString(
+                llvm::core::LLVMBasicBlockAsValue(self.llvm()),
+            ))
+        };
+        write!(fmt, "{}", message.as_ref())
+    }
+}
+
+impl<'a> std::fmt::Debug for BasicBlock<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self)
+    }
+}
@@ -5,13 +5,13 @@ pub struct Binary(NonNull<llvm::object::LLVMOpaqueBinary>);
 
 llvm_inner_impl!(Binary, llvm::object::LLVMOpaqueBinary);
 
-impl<'a> Drop for Binary {
+impl Drop for Binary {
     fn drop(&mut self) {
         unsafe { llvm::object::LLVMDisposeBinary(self.llvm()) }
     }
 }
 
-impl<'a> Binary {
+impl Binary {
     /// Create a new binary object
     pub fn new(ctx: &Context, data: &MemoryBuffer) -> Result<Binary, Error> {
         let mut message = std::ptr::null_mut();
@@ -38,7 +38,7 @@ impl<'a> Binary {
     }
 }
 
-impl<'a> AsRef<[u8]> for Binary {
+impl AsRef<[u8]> for Binary {
     fn as_ref(&self) -> &[u8] {
         let buffer = unsafe { llvm::object::LLVMBinaryCopyMemoryBuffer(self.llvm()) };
         let buf = MemoryBuffer::from_raw(buffer).unwrap();
@@ -2,8 +2,14 @@
 
 use crate::*;
 
+use crate::ssa::Ssa;
+
 /// A `Builder` is used to create `Instruction`s
-pub struct Builder<'a>(NonNull<llvm::LLVMBuilder>, Context<'a>);
+pub struct Builder<'a>(
+    NonNull<llvm::LLVMBuilder>,
+    Context<'a>,
+    std::cell::RefCell<Ssa<'a>>,
+);
 
 llvm_inner_impl!(Builder<'a>, llvm::LLVMBuilder);
 
@@ -13,6 +19,119 @@ impl<'a> Drop for Builder<'a> {
     }
 }
 
+/// Loop context passed to the body of `Builder::loop_` and `Builder::while_loop_with`, it tracks
+/// the loop-carried values and is used to `break_` or `continue_`
+pub struct LoopBuilder<'a> {
+    header: BasicBlock<'a>,
+    exit: BasicBlock<'a>,
+    vars: Vec<InstrPhi<'a>>,
+    exit_vars: Vec<InstrPhi<'a>>,
+}
+
+impl<'a> LoopBuilder<'a> {
+    fn new(
+        build: &Builder<'a>,
+        header: BasicBlock<'a>,
+        exit: BasicBlock<'a>,
+        init: &[Value<'a>],
+    ) -> Result<LoopBuilder<'a>, Error> {
+        let preheader = build.insertion_block()?;
+        build.br(header)?;
+
+        build.position_at_end(exit);
+        let mut exit_vars = Vec::with_capacity(init.len());
+        for v in init {
+            exit_vars.push(build.phi(v.type_of()?, "")?);
+        }
+
+        build.position_at_end(header);
+        let mut vars = Vec::with_capacity(init.len());
+        for v in init {
+            let mut phi = build.phi(v.type_of()?, "")?;
+            phi.add_incoming([(*v, preheader)]);
+            vars.push(phi);
+        }
+
+        Ok(LoopBuilder {
+            header,
+            exit,
+            vars,
+            exit_vars,
+        })
+    }
+
+    fn add_incoming(
+        phis: &[InstrPhi<'a>],
+        values: &[Value<'a>],
+        bb: BasicBlock<'a>,
+    ) -> Result<(), Error> {
+        if phis.len() != values.len() {
+            return Err(Error::LoopValueCount(phis.len(), values.len()));
+        }
+
+        for (phi, v) in phis.iter().zip(values) {
+            let mut phi = *phi;
+            phi.add_incoming([(*v, bb)]);
+        }
+
+        Ok(())
+    }
+
+    /// Loop header block, this is the target of `continue_`
+    pub fn header(&self) -> BasicBlock<'a> {
+        self.header
+    }
+
+    /// Loop exit block, this is the target of `break_`
+    pub fn exit(&self) -> BasicBlock<'a> {
+        self.exit
+    }
+
+    /// Current values of the loop-carried variables
+    pub fn vars(&self) -> Vec<Value<'a>> {
+        self.vars.iter().map(|x| x.0).collect()
+    }
+
+    /// Leave the loop, `values` become the results of the loop and must contain one value for each
+    /// loop-carried variable, otherwise `Error::LoopValueCount` is returned
+    pub fn break_(
+        &self,
+        build: &Builder<'a>,
+        values: impl AsRef<[Value<'a>]>,
+    ) -> Result<Instr<'a>, Error> {
+        let bb = build.insertion_block()?;
+        Self::add_incoming(&self.exit_vars, values.as_ref(), bb)?;
+        build.br(self.exit)
+    }
+
+    /// Jump to the next iteration, `values` become the next values of the loop-carried
+    /// variables and must contain one value for each of them, otherwise `Error::LoopValueCount`
+    /// is returned
+    pub fn continue_(
+        &self,
+        build: &Builder<'a>,
+        values: impl AsRef<[Value<'a>]>,
+    ) -> Result<Instr<'a>, Error> {
+        let bb = build.insertion_block()?;
+        Self::add_incoming(&self.vars, values.as_ref(), bb)?;
+        build.br(self.header)
+    }
+
+    fn finish(self, build: &Builder<'a>) -> Option<Vec<Value<'a>>> {
+        if self.exit.predecessors().is_empty() {
+            for phi in self.exit_vars {
+                phi.to_instr().delete();
+            }
+            self.exit.delete();
+            build.clear_insertion_position();
+            return None;
+        }
+
+        build.position_at_end(self.exit);
+        Some(self.exit_vars.into_iter().map(|x| x.0).collect())
+    }
+}
+
 macro_rules! op {
     (3 : $name:ident, $f:ident) => {
         instr!($name(&self, a: impl AsRef<Value<'a>>, b: impl AsRef<Value<'a>>, c: impl AsRef<Value<'a>>, name: impl AsRef<str>) {
@@ -45,7 +164,7 @@ impl<'a> Builder<'a> {
     /// Create a new builder
     pub fn new(ctx: &Context<'a>) -> Result<Builder<'a>, Error> {
         let b = unsafe { wrap_inner(llvm::core::LLVMCreateBuilderInContext(ctx.llvm()))? };
-        Ok(Builder(b, ctx.clone().clone()))
+        Ok(Builder(b, ctx.clone().clone(), Default::default()))
     }
 
     /// Get the builder's context
@@ -73,7 +192,57 @@ impl<'a> Builder<'a> {
         unsafe { BasicBlock::from_inner(llvm::core::LLVMGetInsertBlock(self.llvm())) }
     }
 
-    /// Declare the body of a function
+    /// Set the debug location attached to new instructions, `None` clears the location
+    pub fn set_current_debug_location(&self, loc: Option<Metadata<'a>>) {
+        let loc = loc.map(|x| x.raw()).unwrap_or(std::ptr::null_mut());
+        unsafe { llvm::core::LLVMSetCurrentDebugLocation2(self.llvm(), loc) }
+    }
+
+    /// Get the debug location attached to new instructions
+    pub fn current_debug_location(&self) -> Result<Metadata<'a>, Error> {
+        let loc = unsafe { llvm::core::LLVMGetCurrentDebugLocation2(self.llvm()) };
+        Metadata::from_raw(self.context(), loc)
+    }
+
+    /// Declare a new mutable local variable, reads and writes using `use_var` and `def_var` are
+    /// converted directly to SSA form without the need for `alloca`/`load`/`store`. Definitions
+    /// are tracked for one function at a time, they are cleared when `def_var`, `use_var` or
+    /// `seal_block` is used in a different function.
+    pub fn declare_var(&self, ty: impl AsRef<Type<'a>>) -> Variable {
+        self.2.borrow_mut().declare(*ty.as_ref())
+    }
+
+    /// Assign a new value to a variable in the current insertion block
+    pub fn def_var(&self, var: Variable, value: impl AsRef<Value<'a>>) -> Result<(), Error> {
+        let bb = self.insertion_block()?;
+        self.2.borrow_mut().write(var, bb, *value.as_ref())
+    }
+
+    /// Get the current value of a variable in the current insertion block, phi nodes are inserted
+    /// as needed
+    ///
+    /// A value read in an unsealed block may be a placeholder phi that is deleted when the block is
+    /// sealed, in that case the returned `Value` must not be used after calling `seal_block`. Uses
+    /// of the value in the IR are updated automatically.
+    pub fn use_var(&self, var: Variable) -> Result<Value<'a>, Error> {
+        let bb = self.insertion_block()?;
+        self.2.borrow_mut().read(self.context(), var, bb)
+    }
+
+    /// Seal a block once all of its predecessors are known, this completes any phi nodes that
+    /// were created in the block while it was unsealed. Trivial phi nodes are removed, which
+    /// invalidates any values returned by `use_var` that refer to them.
+    pub fn seal_block(&self, bb: BasicBlock<'a>) -> Result<(), Error> {
+        self.2.borrow_mut().seal(self.context(), bb)
+    }
+
+    /// Clear all variables and sealed blocks, this is called automatically by `function_body`
+    pub fn reset_vars(&self) {
+        *self.2.borrow_mut() = Ssa::default();
+    }
+
+    /// Declare the body of a function, variables declared using `declare_var` are reset before
+    /// `def` is called
     pub fn function_body<
         T: Into<Value<'a>>,
         F: FnOnce(&Self, BasicBlock<'a>) -> Result<T, Error>,
@@ -82,6 +251,7 @@ impl<'a> Builder<'a> {
         f: Func<'a>,
         def: F,
     ) -> Result<Instr<'a>, Error> {
+        self.reset_vars();
         let entry = BasicBlock::append(self.context(), f.as_ref(), "entry")?;
         self.position_at_end(entry);
         let v = def(self, entry)?;
@@ -103,15 +273,15 @@ impl<'a> Builder<'a> {
         let ctx = self.context();
         let start_bb = self.insertion_block()?;
         let function = start_bb.parent()?;
-        let then_bb = BasicBlock::append(ctx, &function, "then")?;
+        let then_bb = BasicBlock::append(ctx, function, "then")?;
         self.position_at_end(then_bb);
         let then_ = then_(self)?.into();
         let new_then_bb = self.insertion_block()?;
-        let else_bb = BasicBlock::append(ctx, &function, "else")?;
+        let else_bb = BasicBlock::append(ctx, function, "else")?;
         self.position_at_end(else_bb);
         let else_ = else_(self)?.into();
         let new_else_bb = self.insertion_block()?;
-        let merge_bb = BasicBlock::append(ctx, &function, "ifcont")?;
+        let merge_bb = BasicBlock::append(ctx, function, "ifcont")?;
         self.position_at_end(merge_bb);
 
         self.position_at_end(start_bb);
@@ -126,10 +296,155 @@ impl<'a> Builder<'a> {
         self.position_at_end(merge_bb);
 
         let mut phi = self.phi(then_.type_of()?, "ite")?;
-        phi.add_incoming(&[(then_, new_then_bb), (else_, new_else_bb)]);
+        phi.add_incoming([(then_, new_then_bb), (else_, new_else_bb)]);
         Ok(phi)
     }
 
+    /// If-statement without an else branch, the builder is positioned after the if-statement when
+    /// this returns
+    pub fn if_then<Then: FnOnce(&Builder<'a>) -> Result<(), Error>>(
+        &self,
+        cond: impl AsRef<Value<'a>>,
+        then_: Then,
+    ) -> Result<(), Error> {
+        let ctx = self.context();
+        let start_bb = self.insertion_block()?;
+        let function = start_bb.parent()?;
+        let then_bb = BasicBlock::append(ctx, function, "then")?;
+        let merge_bb = BasicBlock::append(ctx, function, "ifcont")?;
+        self.cond_br(cond, then_bb, merge_bb)?;
+
+        self.position_at_end(then_bb);
+        then_(self)?;
+        if self.insertion_block()?.terminator().is_err() {
+            self.br(merge_bb)?;
+        }
+
+        self.position_at_end(merge_bb);
+        Ok(())
+    }
+
+    /// If-statement where either branch may produce no value, a void value or end in a
+    /// terminator. Branches that already end in a terminator do not jump to the merge block. A
+    /// phi is only returned when every branch that reaches the merge block produces a non-void
+    /// value. When neither branch reaches the merge block it is deleted and the builder is left
+    /// without an insertion block.
+    pub fn if_then_else_opt<
+        Then: FnOnce(&Builder<'a>) -> Result<Option<Value<'a>>, Error>,
+        Else: FnOnce(&Builder<'a>) -> Result<Option<Value<'a>>, Error>,
+    >(
+        &self,
+        cond: impl AsRef<Value<'a>>,
+        then_: Then,
+        else_: Else,
+    ) -> Result<Option<InstrPhi<'a>>, Error> {
+        let ctx = self.context();
+        let start_bb = self.insertion_block()?;
+        let function = start_bb.parent()?;
+        let then_bb = BasicBlock::append(ctx, function, "then")?;
+        let else_bb = BasicBlock::append(ctx, function, "else")?;
+        self.cond_br(cond, then_bb, else_bb)?;
+
+        self.position_at_end(then_bb);
+        let then_ = then_(self)?;
+        let new_then_bb = self.insertion_block()?;
+        self.position_at_end(else_bb);
+        let else_ = else_(self)?;
+        let new_else_bb = self.insertion_block()?;
+        let merge_bb = BasicBlock::append(ctx, function, "ifcont")?;
+
+        let mut incoming = Vec::with_capacity(2);
+        let mut has_value = true;
+        let mut reached = false;
+        for (v, bb) in [(then_, new_then_bb), (else_, new_else_bb)] {
+            if bb.terminator().is_ok() {
+                continue;
+            }
+
+            self.position_at_end(bb);
+            self.br(merge_bb)?;
+            reached = true;
+
+            match v {
+                Some(v) if !v.type_of()?.is(TypeKind::LLVMVoidTypeKind) => incoming.push((v, bb)),
+                _ => has_value = false,
+            }
+        }
+
+        if !reached {
+            merge_bb.delete();
+            self.clear_insertion_position();
+            return Ok(None);
+        }
+
+        self.position_at_end(merge_bb);
+
+        if !has_value || incoming.is_empty() {
+            return Ok(None);
+        }
+
+        let mut phi = self.phi(incoming[0].0.type_of()?, "ite")?;
+        phi.add_incoming(incoming);
+        Ok(Some(phi))
+    }
+
+    /// Match-statement, branches to the case matching `value` or `default` when there is no
+    /// match, the result is a phi of all branch results. Branches that already end in a
+    /// terminator do not jump to the merge block. When no branch reaches the merge block it is
+    /// deleted, `None` is returned and the builder is left without an insertion block.
+    #[allow(clippy::type_complexity)]
+    pub fn match_value<E: Into<Value<'a>>, Default: FnOnce(&Builder<'a>) -> Result<E, Error>>(
+        &self,
+        value: impl AsRef<Value<'a>>,
+        cases: &mut [(
+            Const<'a>,
+            &mut dyn FnMut(&Builder<'a>) -> Result<Value<'a>, Error>,
+        )],
+        default: Default,
+    ) -> Result<Option<InstrPhi<'a>>, Error> {
+        let ctx = self.context();
+        let start_bb = self.insertion_block()?;
+        let function = start_bb.parent()?;
+        let default_bb = BasicBlock::append(ctx, function, "default")?;
+        let mut switch = self.switch(value, default_bb, cases.len())?;
+
+        let mut incoming = Vec::with_capacity(cases.len() + 1);
+        for (on_val, case) in cases {
+            let case_bb = BasicBlock::append(ctx, function, "case")?;
+            switch.add_case(on_val, case_bb);
+            self.position_at_end(case_bb);
+            let v = case(self)?;
+            incoming.push((v, self.insertion_block()?));
+        }
+
+        self.position_at_end(default_bb);
+        let default_: Value<'a> = default(self)?.into();
+        incoming.push((default_, self.insertion_block()?));
+
+        let merge_bb = BasicBlock::append(ctx, function, "matchcont")?;
+        let mut reachable = Vec::with_capacity(incoming.len());
+        for (v, bb) in incoming {
+            if bb.terminator().is_ok() {
+                continue;
+            }
+
+            self.position_at_end(bb);
+            self.br(merge_bb)?;
+            reachable.push((v, bb));
+        }
+
+        if reachable.is_empty() {
+            merge_bb.delete();
+            self.clear_insertion_position();
+            return Ok(None);
+        }
+
+        self.position_at_end(merge_bb);
+        let mut phi = self.phi(reachable[0].0.type_of()?, "match")?;
+        phi.add_incoming(reachable);
+        Ok(Some(phi))
+    }
+
     /// For-loop
     pub fn for_loop<
         S: Into<Value<'a>>,
@@ -145,19 +460,54 @@ impl<'a> Builder<'a> {
         step: Step,
         f: F,
     ) -> Result<Value<'a>, Error> {
-        let ctx = self.context();
+        self.for_loop_inner(start.as_ref(), cond, step, f, None)
+    }
+
+    /// For-loop with `!llvm.loop` metadata attached to the latch branch
+    pub fn for_loop_with_hints<
+        S: Into<Value<'a>>,
+        C: Into<Value<'a>>,
+        X: Into<Value<'a>>,
+        Step: FnOnce(&Value<'a>) -> Result<S, Error>,
+        Cond: FnOnce(&Value<'a>) -> Result<C, Error>,
+        F: FnOnce(&Value<'a>) -> Result<X, Error>,
+    >(
+        &self,
+        start: impl AsRef<Value<'a>>,
+        hints: &LoopHints,
+        cond: Cond,
+        step: Step,
+        f: F,
+    ) -> Result<Value<'a>, Error> {
+        self.for_loop_inner(start.as_ref(), cond, step, f, Some(hints))
+    }
 
-        let start = start.as_ref();
+    fn for_loop_inner<
+        S: Into<Value<'a>>,
+        C: Into<Value<'a>>,
+        X: Into<Value<'a>>,
+        Step: FnOnce(&Value<'a>) -> Result<S, Error>,
+        Cond: FnOnce(&Value<'a>) -> Result<C, Error>,
+        F: FnOnce(&Value<'a>) -> Result<X, Error>,
+    >(
+        &self,
+        start: &Value<'a>,
+        cond: Cond,
+        step: Step,
+        f: F,
+        hints: Option<&LoopHints>,
+    ) -> Result<Value<'a>, Error> {
+        let ctx = self.context();
 
         let preheader_bb = self.insertion_block()?;
         let function = preheader_bb.parent()?;
-        let loop_bb = BasicBlock::append(ctx, &function, "loop")?;
+        let loop_bb = BasicBlock::append(ctx, function, "loop")?;
 
         self.br(loop_bb)?;
         self.position_at_end(loop_bb);
 
         let mut var = self.phi(start.type_of()?, "x")?;
-        var.add_incoming(&[(*start, preheader_bb)]);
+        var.add_incoming([(*start, preheader_bb)]);
 
         let _body = f(var.as_ref())?;
 
@@ -166,14 +516,100 @@ impl<'a> Builder<'a> {
         let cond = cond(next_var.as_ref())?.into();
 
         let loop_end_bb = self.insertion_block()?;
-        let after_bb = BasicBlock::append(ctx, &function, "after")?;
-        self.cond_br(cond, loop_bb, after_bb)?;
+        let after_bb = BasicBlock::append(ctx, function, "after")?;
+        let latch = self.cond_br(cond, loop_bb, after_bb)?;
+        if let Some(hints) = hints {
+            hints.attach(latch)?;
+        }
         self.position_at_end(after_bb);
 
-        var.add_incoming(&[(next_var, loop_end_bb)]);
+        var.add_incoming([(next_var, loop_end_bb)]);
         Ok(_body.into())
     }
 
+    /// Loop with loop-carried values, the body is executed until `LoopBuilder::break_` is called.
+    /// The values returned from the body are passed to the next iteration and the values passed
+    /// to `break_` are returned. When the body never calls `break_` the exit block is deleted,
+    /// `None` is returned and the builder is left without an insertion block.
+    pub fn loop_<
+        T: AsRef<[Value<'a>]>,
+        F: FnOnce(&Builder<'a>, &LoopBuilder<'a>, &[Value<'a>]) -> Result<T, Error>,
+    >(
+        &self,
+        init: impl AsRef<[Value<'a>]>,
+        body: F,
+    ) -> Result<Option<Vec<Value<'a>>>, Error> {
+        let ctx = self.context();
+        let function = self.insertion_block()?.parent()?;
+        let header_bb = BasicBlock::append(ctx, function, "loop")?;
+        let exit_bb = BasicBlock::append(ctx, function, "after")?;
+
+        let lp = LoopBuilder::new(self, header_bb, exit_bb, init.as_ref())?;
+        let vars = lp.vars();
+        let next = body(self, &lp, &vars)?;
+        if self.insertion_block()?.terminator().is_err() {
+            lp.continue_(self, next)?;
+        }
+
+        Ok(lp.finish(self))
+    }
+
+    /// While-loop with loop-carried values, `cond` is checked before each iteration. The values
+    /// returned from the body are passed to the next iteration and the final values are
+    /// returned.
+    pub fn while_loop_with<
+        C: Into<Value<'a>>,
+        T: AsRef<[Value<'a>]>,
+        Cond: FnOnce(&Builder<'a>, &[Value<'a>]) -> Result<C, Error>,
+        F: FnOnce(&Builder<'a>, &LoopBuilder<'a>, &[Value<'a>]) -> Result<T, Error>,
+    >(
+        &self,
+        init: impl AsRef<[Value<'a>]>,
+        cond: Cond,
+        body: F,
+    ) -> Result<Vec<Value<'a>>, Error> {
+        let ctx = self.context();
+        let function = self.insertion_block()?.parent()?;
+        let header_bb = BasicBlock::append(ctx, function, "loop")?;
+        let body_bb = BasicBlock::append(ctx, function, "body")?;
+        let exit_bb = BasicBlock::append(ctx, function, "after")?;
+
+        let lp = LoopBuilder::new(self, header_bb, exit_bb, init.as_ref())?;
+        let vars = lp.vars();
+        let cond = cond(self, &vars)?.into();
+        self.cond_br(cond, body_bb, exit_bb)?;
+        LoopBuilder::add_incoming(&lp.exit_vars, &vars, self.insertion_block()?)?;
+
+        self.position_at_end(body_bb);
+        let next = body(self, &lp, &vars)?;
+        if self.insertion_block()?.terminator().is_err() {
+            lp.continue_(self, next)?;
+        }
+
+        lp.finish(self).ok_or(Error::InvalidBasicBlock)
+    }
+
+    /// While-loop, `cond` is checked before each iteration
+    pub fn while_loop<
+        C: Into<Value<'a>>,
+        Cond: FnOnce(&Builder<'a>) -> Result<C, Error>,
+        F: FnOnce(&Builder<'a>, &LoopBuilder<'a>) -> Result<(), Error>,
+    >(
+        &self,
+        cond: Cond,
+        body: F,
+    ) -> Result<(), Error> {
+        self.while_loop_with(
+            [],
+            |build, _| cond(build),
+            |build, lp, _| {
+                body(build, lp)?;
+                Ok([])
+            },
+        )?;
+        Ok(())
+    }
+
     instr!(ret_void(&self) {
         llvm::core::LLVMBuildRetVoid(
             self.llvm(),
@@ -271,6 +707,117 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// Get the module containing the current insertion block
+    pub fn module(&self) -> Result<Module<'a>, Error> {
+        self.insertion_block()?.parent()?.module()
+    }
+
+    /// Call an intrinsic, `types` is used to select an overload
+    pub fn call_intrinsic(
+        &self,
+        intrinsic: impl AsRef<str>,
+        types: impl AsRef<[Type<'a>]>,
+        args: impl AsRef<[Value<'a>]>,
+        name: impl AsRef<str>,
+    ) -> Result<InstrCall<'a>, Error> {
+        let f = self.module()?.intrinsic(intrinsic, types)?;
+        self.call(f, args, name)
+    }
+
+    fn with_overflow(
+        &self,
+        op: &str,
+        a: impl AsRef<Value<'a>>,
+        b: impl AsRef<Value<'a>>,
+        signed: bool,
+        name: impl AsRef<str>,
+    ) -> Result<(Instr<'a>, Instr<'a>), Error> {
+        let a = *a.as_ref();
+        let b = *b.as_ref();
+        let name = name.as_ref();
+        let intrinsic = format!(
+            "llvm.{}{}.with.overflow",
+            if signed { "s" } else { "u" },
+            op
+        );
+        let pair = self.call_intrinsic(intrinsic, [a.type_of()?], [a, b], name)?;
+        let result = self.extract_value(pair, 0, format!("{}.result", name))?;
+        let overflow = self.extract_value(pair, 1, format!("{}.overflow", name))?;
+        Ok((result, overflow))
+    }
+
+    /// Add two integers using `llvm.{s,u}add.with.overflow`, returning the result and an `i1`
+    /// overflow flag
+    pub fn checked_add(
+        &self,
+        a: impl AsRef<Value<'a>>,
+        b: impl AsRef<Value<'a>>,
+        signed: bool,
+        name: impl AsRef<str>,
+    ) -> Result<(Instr<'a>, Instr<'a>), Error> {
+        self.with_overflow("add", a, b, signed, name)
+    }
+
+    /// Subtract two integers using `llvm.{s,u}sub.with.overflow`, returning the result and an
+    /// `i1` overflow flag
+    pub fn checked_sub(
+        &self,
+        a: impl AsRef<Value<'a>>,
+        b: impl AsRef<Value<'a>>,
+        signed: bool,
+        name: impl AsRef<str>,
+    ) -> Result<(Instr<'a>, Instr<'a>), Error> {
+        self.with_overflow("sub", a, b, signed, name)
+    }
+
+    /// Multiply two integers using `llvm.{s,u}mul.with.overflow`, returning the result and an
+    /// `i1` overflow flag
+    pub fn checked_mul(
+        &self,
+        a: impl AsRef<Value<'a>>,
+        b: impl AsRef<Value<'a>>,
+        signed: bool,
+        name: impl AsRef<str>,
+    ) -> Result<(Instr<'a>, Instr<'a>), Error> {
+        self.with_overflow("mul", a, b, signed, name)
+    }
+
+    /// Saturating integer addition using `llvm.{s,u}add.sat`
+    pub fn saturating_add(
+        &self,
+        a: impl AsRef<Value<'a>>,
+        b: impl AsRef<Value<'a>>,
+        signed: bool,
+        name: impl AsRef<str>,
+    ) -> Result<Instr<'a>, Error> {
+        let a = *a.as_ref();
+        let intrinsic = if signed {
+            "llvm.sadd.sat"
+        } else {
+            "llvm.uadd.sat"
+        };
+        let x = self.call_intrinsic(intrinsic, [a.type_of()?], [a, *b.as_ref()], name)?;
+        Ok(x.to_instr())
+    }
+
+    /// Saturating integer subtraction using `llvm.{s,u}sub.sat`
+    pub fn saturating_sub(
+        &self,
+        a: impl AsRef<Value<'a>>,
+        b: impl AsRef<Value<'a>>,
+        signed: bool,
+        name: impl AsRef<str>,
+    ) -> Result<Instr<'a>, Error> {
+        let a = *a.as_ref();
+        let intrinsic = if signed {
+            "llvm.ssub.sat"
+        } else {
+            "llvm.usub.sat"
+        };
+        let x = self.call_intrinsic(intrinsic, [a.type_of()?], [a, *b.as_ref()], name)?;
+        Ok(x.to_instr())
+    }
+
     op!(1: neg, LLVMBuildNeg);
     op!(1: nsw_neg, LLVMBuildNSWNeg);
     op!(1: nuw_neg, LLVMBuildNUWNeg);
@@ -833,7 +1380,11 @@ impl<'a> Builder<'a> {
         let len = values.len();
         let t = f.func_type()?;
 
-        llvm::core::LLVMBuildCall2(self.llvm(), t.as_ref().llvm(), f.as_ref().llvm(), ptr, len as c_uint, name.as_ptr())
+        let call = llvm::core::LLVMBuildCall2(self.llvm(), t.as_ref().llvm(), f.as_ref().llvm(), ptr, len as c_uint, name.as_ptr());
+        if !llvm::core::LLVMIsAFunction(f.as_ref().llvm()).is_null() {
+            llvm::core::LLVMSetInstructionCallConv(call, llvm::core::LLVMGetFunctionCallConv(f.as_ref().llvm()));
+        }
+        call
     });
 
     op!(3: select, LLVMBuildSelect);
@@ -0,0 +1,13 @@
+// `CallInst::setTailCallKind` isn't exposed by the LLVM 14 C API, `LLVMSetTailCall` can only add or
+// remove the `tail` marker
+#include <llvm-c/Core.h>
+#include <llvm/IR/Instructions.h>
+
+extern "C" unsigned llama_get_tail_call_kind(LLVMValueRef call) {
+  return llvm::unwrap<llvm::CallInst>(call)->getTailCallKind();
+}
+
+extern "C" void llama_set_tail_call_kind(LLVMValueRef call, unsigned kind) {
+  llvm::unwrap<llvm::CallInst>(call)->setTailCallKind(
+      static_cast<llvm::CallInst::TailCallKind>(kind));
+}
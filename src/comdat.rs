@@ -0,0 +1,27 @@
+use crate::*;
+
+/// Comdat, groups globals that are kept or discarded together by the linker
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Comdat<'a>(NonNull<llvm::LLVMComdat>, PhantomData<&'a ()>);
+
+llvm_inner_impl!(Comdat<'a>, llvm::LLVMComdat);
+
+/// Comdat conflict resolution
+pub type ComdatSelectionKind = llvm::comdat::LLVMComdatSelectionKind;
+
+impl<'a> Comdat<'a> {
+    /// Wrap an LLVMComdat pointer
+    pub fn from_inner(ptr: *mut llvm::LLVMComdat) -> Result<Comdat<'a>, Error> {
+        Ok(Comdat(wrap_inner(ptr)?, PhantomData))
+    }
+
+    /// Get the selection kind
+    pub fn selection_kind(self) -> ComdatSelectionKind {
+        unsafe { llvm::comdat::LLVMGetComdatSelectionKind(self.llvm()) }
+    }
+
+    /// Set the selection kind
+    pub fn set_selection_kind(&mut self, kind: ComdatSelectionKind) {
+        unsafe { llvm::comdat::LLVMSetComdatSelectionKind(self.llvm(), kind) }
+    }
+}
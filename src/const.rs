@@ -3,7 +3,7 @@
 use crate::*;
 
 /// Constant values
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Const<'a>(pub(crate) Value<'a>);
 
 impl<'a> AsRef<Value<'a>> for Const<'a> {
@@ -48,6 +48,95 @@ impl<'a> Const<'a> {
         )
     });
 
+    /// Create an integer constant from 64-bit words, least significant word first
+    pub fn int_wide(t: impl AsRef<Type<'a>>, words: impl AsRef<[u64]>) -> Result<Const<'a>, Error> {
+        let words = words.as_ref();
+        let v = unsafe {
+            llvm::core::LLVMConstIntOfArbitraryPrecision(
+                t.as_ref().llvm(),
+                words.len() as c_uint,
+                words.as_ptr(),
+            )
+        };
+        Value::from_inner(v)?.to_const()
+    }
+
+    /// Create an integer constant by parsing `s` in the given radix (2, 8, 10, 16 or 36), with an
+    /// optional leading sign
+    pub fn int_from_str(
+        t: impl AsRef<Type<'a>>,
+        s: impl AsRef<str>,
+        radix: u8,
+    ) -> Result<Const<'a>, Error> {
+        let t = t.as_ref();
+        if !t.is(TypeKind::LLVMIntegerTypeKind) {
+            return Err(Error::InvalidType);
+        }
+
+        let s = s.as_ref();
+        let negative = s.starts_with('-');
+        let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+        if ![2, 8, 10, 16, 36].contains(&radix)
+            || digits.is_empty()
+            || !digits.chars().all(|c| c.is_digit(radix as u32))
+        {
+            return Err(Error::InvalidConst);
+        }
+
+        // Compute the magnitude to make sure it fits, LLVM silently truncates it otherwise
+        let mut limbs: Vec<u32> = vec![0];
+        for c in digits.chars() {
+            let mut carry = c.to_digit(radix as u32).unwrap() as u64;
+            for limb in limbs.iter_mut() {
+                let x = *limb as u64 * radix as u64 + carry;
+                *limb = x as u32;
+                carry = x >> 32;
+            }
+            if carry != 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        while limbs.len() > 1 && limbs[limbs.len() - 1] == 0 {
+            limbs.pop();
+        }
+        let top = limbs[limbs.len() - 1];
+        let bits = (limbs.len() - 1) * 32 + (32 - top.leading_zeros() as usize);
+        let power_of_two =
+            top.is_power_of_two() && limbs[..limbs.len() - 1].iter().all(|x| *x == 0);
+
+        let width = t.int_width();
+        let fits = if negative {
+            bits < width || (bits == width && power_of_two)
+        } else {
+            bits <= width
+        };
+        if !fits {
+            return Err(Error::InvalidConst);
+        }
+
+        // Leading zeros are stripped since LLVM estimates the required width from the length
+        let digits = digits.trim_start_matches('0');
+        let s = match (negative, digits.is_empty()) {
+            (_, true) => "0".to_string(),
+            (true, false) => format!("-{}", digits),
+            (false, false) => digits.to_string(),
+        };
+
+        let v = unsafe {
+            llvm::core::LLVMConstIntOfStringAndSize(
+                t.llvm(),
+                s.as_ptr() as *const c_char,
+                s.len() as c_uint,
+                radix,
+            )
+        };
+        Value::from_inner(v)?.to_const()
+    }
+
+    const_func!(block_address(f: Func<'a>, bb: BasicBlock<'a>) {
+        llvm::core::LLVMBlockAddress(f.as_ref().llvm(), bb.llvm())
+    });
+
     const_func!(real(t: impl AsRef<Type<'a>>, i: f64) {
         llvm::core::LLVMConstReal(t.as_ref().llvm(), i as f64)
     });
@@ -107,22 +196,70 @@ impl<'a> Const<'a> {
         )
     });
 
-    pub fn get_unsigned_int(self) -> Option<u64> {
+    fn int_width(self) -> Option<usize> {
         if !self.as_ref().is(ValueKind::LLVMConstantIntValueKind) {
             return None;
         }
 
+        Some(self.as_ref().type_of().ok()?.int_width())
+    }
+
+    pub fn get_unsigned_int(self) -> Option<u64> {
+        if self.int_width()? > 64 {
+            return None;
+        }
+
         unsafe { Some(llvm::core::LLVMConstIntGetZExtValue(self.as_ref().llvm())) }
     }
 
     pub fn get_signed_int(self) -> Option<i64> {
-        if !self.as_ref().is(ValueKind::LLVMConstantIntValueKind) {
+        if self.int_width()? > 64 {
             return None;
         }
 
         unsafe { Some(llvm::core::LLVMConstIntGetSExtValue(self.as_ref().llvm())) }
     }
 
+    /// Get the 64-bit words of an integer constant of any width, least significant word first
+    pub fn get_words(self) -> Option<Vec<u64>> {
+        let width = self.int_width()?;
+        if width <= 64 {
+            return Some(vec![self.get_unsigned_int()?]);
+        }
+
+        let t = self.as_ref().type_of().ok()?;
+        let i64 = Type::i64(&t.context().ok()?).ok()?;
+        (0..width.div_ceil(64))
+            .map(|i| {
+                let shift = Const::int(t, i as i64 * 64).ok()?;
+                self.lshr(shift).ok()?.trunc(i64).ok()?.get_unsigned_int()
+            })
+            .collect()
+    }
+
+    /// Get the value of an integer constant up to 128 bits wide, zero-extended
+    pub fn get_u128(self) -> Option<u128> {
+        if self.int_width()? > 128 {
+            return None;
+        }
+
+        let words = self.get_words()?;
+        Some(
+            words
+                .iter()
+                .rev()
+                .fold(0, |acc, w| (acc << 64) | *w as u128),
+        )
+    }
+
+    /// Get the value of an integer constant up to 128 bits wide, sign-extended
+    pub fn get_i128(self) -> Option<i128> {
+        let width = self.int_width()?;
+        let x = self.get_u128()?;
+        let shift = 128 - width;
+        Some(((x << shift) as i128) >> shift)
+    }
+
     pub fn get_double(self) -> Option<f64> {
         if !self.as_ref().is(ValueKind::LLVMConstantFPValueKind) {
             return None;
@@ -142,10 +279,7 @@ impl<'a> Const<'a> {
         unsafe {
             let s = llvm::core::LLVMGetAsString(self.as_ref().llvm(), &mut size);
             let s = std::slice::from_raw_parts(s as *const u8, strlen(s));
-            match std::str::from_utf8(s) {
-                Ok(x) => Some(x),
-                Err(_) => None,
-            }
+            std::str::from_utf8(s).ok()
         }
     }
 
@@ -208,6 +342,160 @@ impl<'a> Const<'a> {
         Value::from_inner(v)?.to_const()
     }
 
+    /// Recursively decode a constant into a `ConstValue`
+    pub fn to_value(self) -> Result<ConstValue, Error> {
+        let v = self.0;
+        let value = match v.kind() {
+            ValueKind::LLVMConstantIntValueKind if self.int_width() > Some(64) => {
+                ConstValue::WideInt {
+                    bits: self.int_width().ok_or(Error::InvalidConst)?,
+                    words: self.get_words().ok_or(Error::InvalidConst)?,
+                }
+            }
+            ValueKind::LLVMConstantIntValueKind => ConstValue::Int {
+                bits: self.int_width().ok_or(Error::InvalidConst)?,
+                value: self.get_unsigned_int().ok_or(Error::InvalidConst)?,
+            },
+            ValueKind::LLVMConstantFPValueKind => {
+                ConstValue::Float(self.get_double().ok_or(Error::InvalidConst)?)
+            }
+            ValueKind::LLVMConstantPointerNullValueKind => ConstValue::Null,
+            ValueKind::LLVMUndefValueValueKind | ValueKind::LLVMPoisonValueKind => {
+                ConstValue::Undef
+            }
+            ValueKind::LLVMConstantAggregateZeroValueKind => {
+                let t = v.type_of()?;
+                match t.kind() {
+                    TypeKind::LLVMArrayTypeKind
+                        if t.element_type()? == Type::i8(&t.context()?)? =>
+                    {
+                        ConstValue::String(vec![0; t.array_len()])
+                    }
+                    TypeKind::LLVMArrayTypeKind => ConstValue::Array(Self::zeroed(
+                        std::iter::repeat_n(t.element_type()?, t.array_len()),
+                    )?),
+                    TypeKind::LLVMVectorTypeKind => ConstValue::Vector(Self::zeroed(
+                        std::iter::repeat_n(t.element_type()?, t.vector_len()),
+                    )?),
+                    TypeKind::LLVMStructTypeKind => {
+                        ConstValue::Struct(Self::zeroed(t.to_struct_type()?.fields())?)
+                    }
+                    _ => return Err(Error::InvalidConst),
+                }
+            }
+            ValueKind::LLVMConstantDataArrayValueKind if v.is_constant_string() => {
+                let mut size = 0;
+                let data = unsafe {
+                    let s = llvm::core::LLVMGetAsString(v.llvm(), &mut size);
+                    std::slice::from_raw_parts(s as *const u8, size)
+                };
+                ConstValue::String(data.to_vec())
+            }
+            ValueKind::LLVMConstantDataArrayValueKind => {
+                let len = v.type_of()?.array_len();
+                ConstValue::Array(self.elements(len)?)
+            }
+            ValueKind::LLVMConstantDataVectorValueKind => {
+                let len = v.type_of()?.vector_len();
+                ConstValue::Vector(self.elements(len)?)
+            }
+            ValueKind::LLVMConstantArrayValueKind => ConstValue::Array(self.operands()?),
+            ValueKind::LLVMConstantVectorValueKind => ConstValue::Vector(self.operands()?),
+            ValueKind::LLVMConstantStructValueKind => ConstValue::Struct(self.operands()?),
+            _ => return Err(Error::InvalidConst),
+        };
+        Ok(value)
+    }
+
+    /// Decode a constant into a Rust value
+    pub fn to_rust<T: FromConst>(self) -> Result<T, Error> {
+        T::from_const(&self.to_value()?).ok_or(Error::InvalidConst)
+    }
+
+    fn zeroed(types: impl IntoIterator<Item = Type<'a>>) -> Result<Vec<ConstValue>, Error> {
+        types
+            .into_iter()
+            .map(|t| Const::null(t)?.to_value())
+            .collect()
+    }
+
+    fn elements(self, len: usize) -> Result<Vec<ConstValue>, Error> {
+        (0..len).map(|i| self.get_element(i)?.to_value()).collect()
+    }
+
+    fn operand(self, index: usize) -> Result<Const<'a>, Error> {
+        unsafe {
+            Value::from_inner(llvm::core::LLVMGetOperand(
+                self.as_ref().llvm(),
+                index as c_uint,
+            ))?
+            .to_const()
+        }
+    }
+
+    fn operand_count(self) -> usize {
+        unsafe { llvm::core::LLVMGetNumOperands(self.as_ref().llvm()) as usize }
+    }
+
+    fn operands(self) -> Result<Vec<ConstValue>, Error> {
+        (0..self.operand_count())
+            .map(|i| self.operand(i)?.to_value())
+            .collect()
+    }
+
+    /// Evaluate a constant into its in-memory representation for the given target
+    ///
+    /// Returns `Error::InvalidConst` if the constant contains pointers to globals, use
+    /// `Const::evaluate_with_relocations` in that case
+    pub fn evaluate(self, data: &TargetData<'a>) -> Result<Vec<u8>, Error> {
+        let (bytes, relocations) = self.evaluate_with_relocations(data)?;
+        if !relocations.is_empty() {
+            return Err(Error::InvalidConst);
+        }
+        Ok(bytes)
+    }
+
+    /// Evaluate a constant into its in-memory representation for the given target, pointers to
+    /// globals are left zeroed and reported as relocations
+    pub fn evaluate_with_relocations(
+        self,
+        data: &TargetData<'a>,
+    ) -> Result<(Vec<u8>, Vec<Relocation>), Error> {
+        let t = self.as_ref().type_of()?;
+        let mut image = Image {
+            data,
+            bytes: vec![0; data.abi_size_of_type(t)],
+            relocations: Vec::new(),
+        };
+        image.write(self, 0)?;
+        Ok((image.bytes, image.relocations))
+    }
+
+    /// Create a constant array from a slice of integers or floats
+    ///
+    /// Arrays of 8, 16, 32 and 64-bit integers, `f32` and `f64` are copied directly into a
+    /// `ConstantDataArray`, other element types create one constant per element
+    pub fn data_array<T: ConstData<'a>>(
+        ctx: &Context<'a>,
+        data: impl AsRef<[T]>,
+    ) -> Result<Const<'a>, Error> {
+        T::data_array(ctx, data.as_ref())
+    }
+
+    /// Create a constant `i8` array containing `data`, without a null terminator
+    pub fn bytes(ctx: &Context<'a>, data: impl AsRef<[u8]>) -> Result<Const<'a>, Error> {
+        let data = data.as_ref();
+        let v = unsafe {
+            llvm::core::LLVMConstStringInContext(
+                ctx.llvm(),
+                data.as_ptr() as *const c_char,
+                data.len() as c_uint,
+                1,
+            )
+        };
+        Value::from_inner(v)?.to_const()
+    }
+
     pub fn op_code(self) -> OpCode {
         unsafe { llvm::core::LLVMGetConstOpcode(self.as_ref().llvm()) }
     }
@@ -750,3 +1038,518 @@ impl<'a> Const<'a> {
         llvm::core::LLVMConstInsertValue(self.as_ref().llvm(), x.as_ref().llvm(), idx.as_mut_ptr(), num as u32)
     });
 }
+
+/// Rust types that can be used as elements of a constant data array, see `Const::data_array`
+pub trait ConstData<'a>: LLVMType<'a> + Copy {
+    /// Convert a single value to a constant of type `t`
+    fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error>;
+
+    /// Create a constant array containing `data`, the default implementation converts each
+    /// element using `to_const`
+    fn data_array(ctx: &Context<'a>, data: &[Self]) -> Result<Const<'a>, Error> {
+        let t = Self::llvm_type(ctx)?;
+        let mut vals = Vec::with_capacity(data.len());
+        for x in data {
+            vals.push(x.to_const(t)?.as_ref().llvm());
+        }
+        let v = unsafe {
+            llvm::core::LLVMConstArray(t.llvm(), vals.as_mut_ptr(), vals.len() as c_uint)
+        };
+        Value::from_inner(v)?.to_const()
+    }
+}
+
+/// Create a `ConstantDataArray` by copying `data`, the element type must be an 8, 16, 32 or 64-bit
+/// integer, `float` or `double` with the same in-memory representation as `T`
+fn raw_data_array<'a, T: ConstData<'a>>(ctx: &Context<'a>, data: &[T]) -> Result<Const<'a>, Error> {
+    let t = T::llvm_type(ctx)?;
+    let v = unsafe {
+        llama_const_data_array(
+            t.llvm(),
+            data.as_ptr() as *const c_char,
+            std::mem::size_of_val(data),
+            data.len() as u64,
+        )
+    };
+    Value::from_inner(v)?.to_const()
+}
+
+macro_rules! const_data_int {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> ConstData<'a> for $t {
+                fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error> {
+                    Const::int(t, self as i64)
+                }
+
+                fn data_array(ctx: &Context<'a>, data: &[$t]) -> Result<Const<'a>, Error> {
+                    raw_data_array(ctx, data)
+                }
+            }
+        )*
+    };
+}
+
+const_data_int!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize);
+
+impl<'a> ConstData<'a> for bool {
+    fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error> {
+        Const::int(t, self as i64)
+    }
+}
+
+impl<'a> ConstData<'a> for u128 {
+    fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error> {
+        Const::int_wide(t, [self as u64, (self >> 64) as u64])
+    }
+}
+
+impl<'a> ConstData<'a> for i128 {
+    fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error> {
+        (self as u128).to_const(t)
+    }
+}
+
+impl<'a> ConstData<'a> for f32 {
+    fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error> {
+        Const::real(t, self as f64)
+    }
+
+    fn data_array(ctx: &Context<'a>, data: &[f32]) -> Result<Const<'a>, Error> {
+        raw_data_array(ctx, data)
+    }
+}
+
+impl<'a> ConstData<'a> for f64 {
+    fn to_const(self, t: Type<'a>) -> Result<Const<'a>, Error> {
+        Const::real(t, self)
+    }
+
+    fn data_array(ctx: &Context<'a>, data: &[f64]) -> Result<Const<'a>, Error> {
+        raw_data_array(ctx, data)
+    }
+}
+
+/// Decoded constant value, see `Const::to_value`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstValue {
+    /// Integer with a width of up to 64 bits, `value` is zero-extended
+    Int {
+        /// Bit width
+        bits: usize,
+        /// Zero-extended value
+        value: u64,
+    },
+    /// Integer wider than 64 bits
+    WideInt {
+        /// Bit width
+        bits: usize,
+        /// 64-bit words with the least significant word first
+        words: Vec<u64>,
+    },
+    /// Floating point number
+    Float(f64),
+    /// Constant `i8` array, including any null terminator
+    String(Vec<u8>),
+    /// Null pointer
+    Null,
+    /// Undef or poison value
+    Undef,
+    /// Array elements
+    Array(Vec<ConstValue>),
+    /// Vector elements
+    Vector(Vec<ConstValue>),
+    /// Struct fields
+    Struct(Vec<ConstValue>),
+}
+
+/// Rust types that can be decoded from a `ConstValue`, see `Const::to_rust`
+pub trait FromConst: Sized {
+    /// Decode a constant value, returns `None` if the value doesn't match `Self`
+    fn from_const(value: &ConstValue) -> Option<Self>;
+}
+
+impl FromConst for ConstValue {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+fn sign_extend(bits: usize, value: u64) -> i64 {
+    if bits == 0 || bits >= 64 {
+        return value as i64;
+    }
+
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+macro_rules! from_const_int {
+    ($($t:ty),*) => {
+        $(
+            /// Returns `None` when the value doesn't fit
+            impl FromConst for $t {
+                fn from_const(value: &ConstValue) -> Option<Self> {
+                    match value {
+                        ConstValue::Int { value, .. } => std::convert::TryFrom::try_from(*value).ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_const_signed_int {
+    ($($t:ty),*) => {
+        $(
+            /// The value is sign-extended from its bit width, returns `None` when it doesn't fit
+            impl FromConst for $t {
+                fn from_const(value: &ConstValue) -> Option<Self> {
+                    match value {
+                        ConstValue::Int { bits, value } => {
+                            std::convert::TryFrom::try_from(sign_extend(*bits, *value)).ok()
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_const_int!(u8, u16, u32, u64, usize);
+from_const_signed_int!(i8, i16, i32, i64, isize);
+
+fn words_to_i128(bits: usize, words: &[u64], signed: bool) -> Option<i128> {
+    let lo = words.first().copied().unwrap_or(0) as u128;
+    let hi = words.get(1).copied().unwrap_or(0) as u128;
+    let mut x = (lo | hi << 64) as i128;
+    if signed && bits < 128 {
+        let shift = 128 - bits;
+        x = (x << shift) >> shift;
+    }
+
+    // Any remaining words must be an extension of the low 128 bits
+    let ext = if signed && x < 0 { u64::MAX } else { 0 };
+    let ext_bits = bits.saturating_sub(128);
+    for (i, w) in words.iter().enumerate().skip(2) {
+        let used = ext_bits.saturating_sub((i - 2) * 64).min(64);
+        let mask = if used == 64 {
+            u64::MAX
+        } else {
+            (1 << used) - 1
+        };
+        if w & mask != ext & mask {
+            return None;
+        }
+    }
+    Some(x)
+}
+
+/// Returns `None` when the value doesn't fit
+impl FromConst for u128 {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::Int { value, .. } => Some(*value as u128),
+            ConstValue::WideInt { bits, words } => {
+                words_to_i128(*bits, words, false).map(|x| x as u128)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The value is sign-extended from its bit width, returns `None` when it doesn't fit
+impl FromConst for i128 {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::Int { bits, value } => Some(sign_extend(*bits, *value) as i128),
+            ConstValue::WideInt { bits, words } => words_to_i128(*bits, words, true),
+            _ => None,
+        }
+    }
+}
+
+impl FromConst for bool {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::Int { value: 0, .. } => Some(false),
+            ConstValue::Int { value: 1, .. } => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl FromConst for f32 {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        f64::from_const(value).map(|x| x as f32)
+    }
+}
+
+impl FromConst for f64 {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+/// Strings are decoded up to the first null byte
+impl FromConst for String {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::String(x) => {
+                let len = x.iter().position(|c| *c == 0).unwrap_or(x.len());
+                String::from_utf8(x[..len].to_vec()).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Arrays and vectors are decoded element-wise, `i8` arrays are decoded as bytes
+impl<T: FromConst> FromConst for Vec<T> {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::Array(x) | ConstValue::Vector(x) => x.iter().map(T::from_const).collect(),
+            ConstValue::String(x) => x
+                .iter()
+                .map(|c| {
+                    T::from_const(&ConstValue::Int {
+                        bits: 8,
+                        value: *c as u64,
+                    })
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: FromConst, const N: usize> FromConst for [T; N] {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        std::convert::TryFrom::try_from(Vec::<T>::from_const(value)?).ok()
+    }
+}
+
+/// Null pointers are decoded as `None`
+impl<T: FromConst> FromConst for Option<T> {
+    fn from_const(value: &ConstValue) -> Option<Self> {
+        match value {
+            ConstValue::Null => Some(None),
+            x => T::from_const(x).map(Some),
+        }
+    }
+}
+
+macro_rules! from_const_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: FromConst),+> FromConst for ($($t,)+) {
+            fn from_const(value: &ConstValue) -> Option<Self> {
+                match value {
+                    ConstValue::Struct(fields) => {
+                        let mut fields = fields.iter();
+                        let x = ($($t::from_const(fields.next()?)?,)+);
+                        if fields.next().is_some() {
+                            return None;
+                        }
+                        Some(x)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+from_const_tuple!(A);
+from_const_tuple!(A, B);
+from_const_tuple!(A, B, C);
+from_const_tuple!(A, B, C, D);
+from_const_tuple!(A, B, C, D, E);
+from_const_tuple!(A, B, C, D, E, F);
+from_const_tuple!(A, B, C, D, E, F, G);
+from_const_tuple!(A, B, C, D, E, F, G, H);
+
+/// Slot that refers to a global, see `Const::evaluate_with_relocations`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Relocation {
+    /// Offset of the slot
+    pub offset: usize,
+    /// Size of the slot in bytes, this is smaller than a pointer when the address is truncated
+    pub size: usize,
+    /// Name of the referenced global
+    pub symbol: String,
+    /// Byte offset added to the address of `symbol`
+    pub addend: i64,
+}
+
+/// Multiply a GEP index by an element size, returning an error on overflow
+fn scale(index: i64, size: usize) -> Result<i64, Error> {
+    let size: i64 = std::convert::TryFrom::try_from(size).map_err(|_| Error::InvalidConst)?;
+    index.checked_mul(size).ok_or(Error::InvalidConst)
+}
+
+struct Image<'a, 'b> {
+    data: &'b TargetData<'a>,
+    bytes: Vec<u8>,
+    relocations: Vec<Relocation>,
+}
+
+impl<'a, 'b> Image<'a, 'b> {
+    fn write(&mut self, c: Const<'a>, offset: usize) -> Result<(), Error> {
+        let t = c.as_ref().type_of()?;
+        match c.as_ref().kind() {
+            ValueKind::LLVMUndefValueValueKind
+            | ValueKind::LLVMPoisonValueKind
+            | ValueKind::LLVMConstantAggregateZeroValueKind
+            | ValueKind::LLVMConstantPointerNullValueKind => Ok(()),
+            ValueKind::LLVMConstantIntValueKind => {
+                let words = c.get_words().ok_or(Error::InvalidConst)?;
+                self.write_int(&words, offset, self.data.store_size_of_type(t));
+                Ok(())
+            }
+            ValueKind::LLVMConstantFPValueKind => {
+                let bits = self.data.size_of_type_in_bits(t);
+                let i = c.bit_cast(Type::int(&t.context()?, bits)?)?;
+                self.write(i, offset)
+            }
+            ValueKind::LLVMConstantStructValueKind => {
+                for i in 0..c.operand_count() {
+                    let field_offset = self.data.struct_offset_of_element(t, i);
+                    self.write(c.operand(i)?, offset + field_offset)?;
+                }
+                Ok(())
+            }
+            ValueKind::LLVMConstantArrayValueKind
+            | ValueKind::LLVMConstantVectorValueKind
+            | ValueKind::LLVMConstantDataArrayValueKind
+            | ValueKind::LLVMConstantDataVectorValueKind => {
+                let element_type = t.element_type()?;
+                let stride = if t.is(TypeKind::LLVMArrayTypeKind) {
+                    self.data.abi_size_of_type(element_type)
+                } else {
+                    let bits = self.data.size_of_type_in_bits(element_type);
+                    if !bits.is_multiple_of(8) {
+                        return Err(Error::InvalidConst);
+                    }
+                    bits / 8
+                };
+
+                let is_data = c.as_ref().is(ValueKind::LLVMConstantDataArrayValueKind)
+                    || c.as_ref().is(ValueKind::LLVMConstantDataVectorValueKind);
+                let len = if t.is(TypeKind::LLVMArrayTypeKind) {
+                    t.array_len()
+                } else {
+                    t.vector_len()
+                };
+                for i in 0..len {
+                    let element = if is_data {
+                        c.get_element(i)?
+                    } else {
+                        c.operand(i)?
+                    };
+                    self.write(element, offset + i * stride)?;
+                }
+                Ok(())
+            }
+            ValueKind::LLVMFunctionValueKind
+            | ValueKind::LLVMGlobalVariableValueKind
+            | ValueKind::LLVMGlobalAliasValueKind
+            | ValueKind::LLVMGlobalIFuncValueKind
+            | ValueKind::LLVMConstantExprValueKind => {
+                let size = self.data.store_size_of_type(t);
+                match self.address(c)? {
+                    (Some(symbol), addend) => {
+                        self.relocations.push(Relocation {
+                            offset,
+                            size,
+                            symbol,
+                            addend,
+                        });
+                    }
+                    (None, value) => self.write_int(&[value as u64], offset, size),
+                }
+                Ok(())
+            }
+            _ => Err(Error::InvalidConst),
+        }
+    }
+
+    fn write_int(&mut self, words: &[u64], offset: usize, size: usize) {
+        let dest = &mut self.bytes[offset..offset + size];
+        for (i, byte) in dest.iter_mut().enumerate() {
+            let word = words.get(i / 8).copied().unwrap_or(0);
+            *byte = (word >> (8 * (i % 8))) as u8;
+        }
+
+        if self.data.byte_order() == ByteOrder::LLVMBigEndian {
+            dest.reverse();
+        }
+    }
+
+    /// Evaluate a pointer or pointer-sized integer as a symbol and an offset
+    fn address(&self, c: Const<'a>) -> Result<(Option<String>, i64), Error> {
+        match c.as_ref().kind() {
+            ValueKind::LLVMConstantPointerNullValueKind
+            | ValueKind::LLVMUndefValueValueKind
+            | ValueKind::LLVMPoisonValueKind => Ok((None, 0)),
+            ValueKind::LLVMConstantIntValueKind => {
+                Ok((None, c.get_signed_int().ok_or(Error::InvalidConst)?))
+            }
+            ValueKind::LLVMFunctionValueKind
+            | ValueKind::LLVMGlobalVariableValueKind
+            | ValueKind::LLVMGlobalAliasValueKind
+            | ValueKind::LLVMGlobalIFuncValueKind => Ok((Some(c.as_ref().name()?.into()), 0)),
+            ValueKind::LLVMConstantExprValueKind => match c.op_code() {
+                OpCode::LLVMBitCast
+                | OpCode::LLVMAddrSpaceCast
+                | OpCode::LLVMIntToPtr
+                | OpCode::LLVMPtrToInt => self.address(c.operand(0)?),
+                OpCode::LLVMAdd => {
+                    match (self.address(c.operand(0)?)?, self.address(c.operand(1)?)?) {
+                        ((a, x), (None, y)) | ((None, x), (a, y)) => Ok((a, x.wrapping_add(y))),
+                        _ => Err(Error::InvalidConst),
+                    }
+                }
+                OpCode::LLVMSub => {
+                    match (self.address(c.operand(0)?)?, self.address(c.operand(1)?)?) {
+                        ((a, x), (None, y)) => Ok((a, x.wrapping_sub(y))),
+                        ((Some(a), x), (Some(b), y)) if a == b => Ok((None, x.wrapping_sub(y))),
+                        _ => Err(Error::InvalidConst),
+                    }
+                }
+                OpCode::LLVMGetElementPtr => {
+                    let (symbol, base) = self.address(c.operand(0)?)?;
+                    let mut t = unsafe {
+                        Type::from_inner(llvm::core::LLVMGetGEPSourceElementType(
+                            c.as_ref().llvm(),
+                        ))?
+                    };
+                    let mut offset = base;
+                    for i in 1..c.operand_count() {
+                        let index = c.operand(i)?.get_signed_int().ok_or(Error::InvalidConst)?;
+                        let delta = if i == 1 {
+                            scale(index, self.data.abi_size_of_type(t))?
+                        } else if t.is(TypeKind::LLVMStructTypeKind) {
+                            let index: usize = std::convert::TryFrom::try_from(index)
+                                .map_err(|_| Error::InvalidConst)?;
+                            let field_offset = self.data.struct_offset_of_element(t, index);
+                            t = t.to_struct_type()?.field(index)?;
+                            scale(1, field_offset)?
+                        } else {
+                            t = t.element_type()?;
+                            scale(index, self.data.abi_size_of_type(t))?
+                        };
+                        offset = offset.checked_add(delta).ok_or(Error::InvalidConst)?;
+                    }
+                    Ok((symbol, offset))
+                }
+                _ => Err(Error::InvalidConst),
+            },
+            _ => Err(Error::InvalidConst),
+        }
+    }
+}
@@ -16,9 +16,122 @@ impl<'a> Drop for Context<'a> {
         }
 
         unsafe { llvm::core::LLVMContextDispose(self.llvm()) }
+
+        let callbacks = CALLBACKS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(self.0.as_ptr() as usize));
+        if let Some(callbacks) = callbacks {
+            unsafe { callbacks.free() }
+        }
+    }
+}
+
+/// A diagnostic reported by LLVM
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// Diagnostic severity
+    pub severity: DiagnosticSeverity,
+
+    /// Diagnostic message
+    pub message: String,
+}
+
+/// Diagnostics collected using `Context::collect_diagnostics`
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics(std::rc::Rc<std::cell::RefCell<Vec<Diagnostic>>>);
+
+impl Diagnostics {
+    /// Take all diagnostics collected so far
+    pub fn take(&self) -> Vec<Diagnostic> {
+        self.0.replace(Vec::new())
+    }
+
+    /// Returns true when an error has been collected
+    pub fn has_errors(&self) -> bool {
+        self.0
+            .borrow()
+            .iter()
+            .any(|d| d.severity == DiagnosticSeverity::LLVMDSError)
+    }
+}
+
+type DiagnosticHandler = Box<dyn FnMut(Diagnostic)>;
+type YieldCallback = Box<dyn FnMut()>;
+
+/// Closures registered with a context, stored as raw pointers since LLVM only keeps a `void*`
+#[derive(Default)]
+struct Callbacks {
+    diagnostic: usize,
+    yield_callback: usize,
+}
+
+impl Callbacks {
+    unsafe fn free(self) {
+        free_diagnostic_handler(self.diagnostic);
+        free_yield_callback(self.yield_callback);
+    }
+}
+
+unsafe fn free_diagnostic_handler(ptr: usize) {
+    if ptr != 0 {
+        drop(Box::from_raw(ptr as *mut DiagnosticHandler))
+    }
+}
+
+unsafe fn free_yield_callback(ptr: usize) {
+    if ptr != 0 {
+        drop(Box::from_raw(ptr as *mut YieldCallback))
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref CALLBACKS: std::sync::Mutex<std::collections::HashMap<usize, Callbacks>> =
+        std::sync::Mutex::new(std::collections::HashMap::new());
+}
+
+thread_local! {
+    static PANIC: std::cell::RefCell<Option<Box<dyn std::any::Any + Send>>> =
+        std::cell::RefCell::new(None);
+}
+
+/// Run a callback from an `extern "C"` trampoline, a panic can't unwind through LLVM so it is
+/// stored until `resume_callback_panic` is called. Callbacks are skipped while a panic is pending
+fn catch_callback_panic(f: impl FnOnce()) {
+    if PANIC.with(|p| p.borrow().is_some()) {
+        return;
+    }
+
+    if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        PANIC.with(|p| *p.borrow_mut() = Some(e));
+    }
+}
+
+/// Re-raise a panic from a diagnostic handler or yield callback, this should be called after any
+/// LLVM function that may call them
+pub(crate) fn resume_callback_panic() {
+    if let Some(e) = PANIC.with(|p| p.borrow_mut().take()) {
+        std::panic::resume_unwind(e)
     }
 }
 
+extern "C" fn diagnostic_handler(info: llvm::prelude::LLVMDiagnosticInfoRef, data: *mut c_void) {
+    let f = unsafe { &mut *(data as *mut DiagnosticHandler) };
+    let severity = unsafe { llvm::core::LLVMGetDiagInfoSeverity(info) };
+    let message = unsafe { Message::from_raw(llvm::core::LLVMGetDiagInfoDescription(info)) };
+    catch_callback_panic(|| {
+        f(Diagnostic {
+            severity,
+            message: message.into(),
+        })
+    })
+}
+
+extern "C" fn yield_callback(_ctx: llvm::prelude::LLVMContextRef, data: *mut c_void) {
+    let f = unsafe { &mut *(data as *mut YieldCallback) };
+    catch_callback_panic(f)
+}
+
 static INIT: std::sync::Once = std::sync::Once::new();
 
 impl<'a> Clone for Context<'a> {
@@ -98,6 +211,66 @@ impl<'a> Context<'a> {
         unsafe { Type::from_inner(llvm::core::LLVMGetTypeByName2(self.llvm(), name.as_ptr())) }
     }
 
-    // TODO: LLVMContextGetDiagnosticHandler, LLVMContextSetDiagnosticHandler,
-    // LLVMContextSetYieldCallback, ...
+    fn replace_callback(&self, f: impl FnOnce(&mut Callbacks) -> usize) -> usize {
+        let mut callbacks = CALLBACKS.lock().unwrap_or_else(|e| e.into_inner());
+        f(callbacks.entry(self.0.as_ptr() as usize).or_default())
+    }
+
+    /// Set a handler for diagnostics, this replaces the default handler which prints to stderr
+    /// and exits the process on errors. A panic in the handler is re-raised once control returns
+    /// from LLVM
+    pub fn set_diagnostic_handler(&self, f: impl FnMut(Diagnostic) + 'static) {
+        let f: Box<DiagnosticHandler> = Box::new(Box::new(f));
+        let ptr = Box::into_raw(f);
+        unsafe {
+            llvm::core::LLVMContextSetDiagnosticHandler(
+                self.llvm(),
+                Some(diagnostic_handler),
+                ptr as *mut c_void,
+            )
+        }
+        let old = self.replace_callback(|c| std::mem::replace(&mut c.diagnostic, ptr as usize));
+        unsafe { free_diagnostic_handler(old) }
+    }
+
+    /// Remove the diagnostic handler, restoring the default behavior
+    pub fn clear_diagnostic_handler(&self) {
+        unsafe {
+            llvm::core::LLVMContextSetDiagnosticHandler(self.llvm(), None, std::ptr::null_mut())
+        }
+        let old = self.replace_callback(|c| std::mem::take(&mut c.diagnostic));
+        unsafe { free_diagnostic_handler(old) }
+    }
+
+    /// Collect diagnostics instead of printing them, this replaces any existing diagnostic
+    /// handler
+    pub fn collect_diagnostics(&self) -> Diagnostics {
+        let diagnostics = Diagnostics::default();
+        let d = diagnostics.clone();
+        self.set_diagnostic_handler(move |x| d.0.borrow_mut().push(x));
+        diagnostics
+    }
+
+    /// Set a callback that is called periodically by LLVM during long running operations, such as
+    /// running a `PassManager`. A panic in the callback is re-raised once control returns from LLVM
+    pub fn set_yield_callback(&self, f: impl FnMut() + 'static) {
+        let f: Box<YieldCallback> = Box::new(Box::new(f));
+        let ptr = Box::into_raw(f);
+        unsafe {
+            llvm::core::LLVMContextSetYieldCallback(
+                self.llvm(),
+                Some(yield_callback),
+                ptr as *mut c_void,
+            )
+        }
+        let old = self.replace_callback(|c| std::mem::replace(&mut c.yield_callback, ptr as usize));
+        unsafe { free_yield_callback(old) }
+    }
+
+    /// Remove the yield callback
+    pub fn clear_yield_callback(&self) {
+        unsafe { llvm::core::LLVMContextSetYieldCallback(self.llvm(), None, std::ptr::null_mut()) }
+        let old = self.replace_callback(|c| std::mem::take(&mut c.yield_callback));
+        unsafe { free_yield_callback(old) }
+    }
 }
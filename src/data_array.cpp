@@ -0,0 +1,12 @@
+// `ConstantDataArray::getRaw` isn't exposed by the LLVM C API, without it every element of a data
+// array has to be created as a separate constant
+#include <llvm-c/Core.h>
+#include <llvm/IR/Constants.h>
+#include <llvm/IR/Type.h>
+#include <llvm/IR/Value.h>
+
+extern "C" LLVMValueRef llama_const_data_array(LLVMTypeRef element_type, const char *data,
+                                               size_t len, uint64_t num_elements) {
+  return llvm::wrap(llvm::ConstantDataArray::getRaw(llvm::StringRef(data, len), num_elements,
+                                                    llvm::unwrap(element_type)));
+}
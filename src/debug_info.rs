@@ -0,0 +1,465 @@
+use crate::*;
+
+/// Debug info flags
+pub type DIFlags = llvm::debuginfo::LLVMDIFlags;
+
+/// Source languages known by DWARF
+pub type DWARFSourceLanguage = llvm::debuginfo::LLVMDWARFSourceLanguage;
+
+/// DWARF base type encoding, see `DW_ATE_*` in the DWARF standard
+pub type DWARFTypeEncoding = llvm::debuginfo::LLVMDWARFTypeEncoding;
+
+/// `DW_ATE_boolean`
+pub const DW_ATE_BOOLEAN: DWARFTypeEncoding = 0x02;
+/// `DW_ATE_float`
+pub const DW_ATE_FLOAT: DWARFTypeEncoding = 0x04;
+/// `DW_ATE_signed`
+pub const DW_ATE_SIGNED: DWARFTypeEncoding = 0x05;
+/// `DW_ATE_signed_char`
+pub const DW_ATE_SIGNED_CHAR: DWARFTypeEncoding = 0x06;
+/// `DW_ATE_unsigned`
+pub const DW_ATE_UNSIGNED: DWARFTypeEncoding = 0x07;
+/// `DW_ATE_unsigned_char`
+pub const DW_ATE_UNSIGNED_CHAR: DWARFTypeEncoding = 0x08;
+
+/// `DebugInfoBuilder` is used to create DWARF debug info metadata for a module, the module is
+/// borrowed until the builder is dropped
+///
+/// ```compile_fail,E0505
+/// use llama::*;
+///
+/// fn main() -> Result<(), Error> {
+///     let ctx = Context::new()?;
+///     let module = Module::new(&ctx, "test")?;
+///     let dib = DebugInfoBuilder::new(&module)?;
+///     drop(module);
+///     dib.finalize();
+///     Ok(())
+/// }
+/// ```
+pub struct DebugInfoBuilder<'m, 'a>(
+    NonNull<llvm::LLVMOpaqueDIBuilder>,
+    Context<'a>,
+    std::cell::Cell<bool>,
+    PhantomData<&'m Module<'a>>,
+);
+
+impl<'m, 'a> LLVM<llvm::LLVMOpaqueDIBuilder> for DebugInfoBuilder<'m, 'a> {
+    fn llvm(&self) -> *mut llvm::LLVMOpaqueDIBuilder {
+        self.0.as_ptr()
+    }
+}
+
+impl<'m, 'a> Drop for DebugInfoBuilder<'m, 'a> {
+    fn drop(&mut self) {
+        self.finalize();
+        unsafe { llvm::debuginfo::LLVMDisposeDIBuilder(self.llvm()) }
+    }
+}
+
+fn raw_or_null(md: Option<Metadata>) -> *mut llvm::LLVMOpaqueMetadata {
+    md.map(|x| x.raw()).unwrap_or(std::ptr::null_mut())
+}
+
+impl<'m, 'a> DebugInfoBuilder<'m, 'a> {
+    /// Create a new debug info builder, this also sets the "Debug Info Version" module flag if it
+    /// hasn't been set already
+    pub fn new(module: &'m Module<'a>) -> Result<DebugInfoBuilder<'m, 'a>, Error> {
+        let ctx = module.context()?;
+        unsafe {
+            if llvm::debuginfo::LLVMGetModuleDebugMetadataVersion(module.llvm()) == 0 {
+                let i32 = Type::i32(&ctx)?;
+                let version = Const::int(i32, llvm::debuginfo::LLVMDebugMetadataVersion() as i64)?;
+                let key = "Debug Info Version";
+                llvm::core::LLVMAddModuleFlag(
+                    module.llvm(),
+                    ModuleFlagBehavior::LLVMModuleFlagBehaviorWarning,
+                    key.as_ptr() as *const c_char,
+                    key.len(),
+                    llvm::core::LLVMValueAsMetadata(version.as_ref().llvm()),
+                );
+            }
+
+            let b = wrap_inner(llvm::debuginfo::LLVMCreateDIBuilder(module.llvm()))?;
+            Ok(DebugInfoBuilder(
+                b,
+                ctx,
+                std::cell::Cell::new(false),
+                PhantomData,
+            ))
+        }
+    }
+
+    fn metadata(&self, md: *mut llvm::LLVMOpaqueMetadata) -> Result<Metadata<'a>, Error> {
+        Metadata::from_raw(&self.1, md)
+    }
+
+    /// Construct any deferred debug info, this is called automatically when the builder is
+    /// dropped
+    pub fn finalize(&self) {
+        if !self.2.replace(true) {
+            unsafe { llvm::debuginfo::LLVMDIBuilderFinalize(self.llvm()) }
+        }
+    }
+
+    /// Finalize a single subprogram, no new variables may be added to it afterwards
+    pub fn finalize_subprogram(&self, subprogram: Metadata<'a>) {
+        unsafe { llvm::debuginfo::LLVMDIBuilderFinalizeSubprogram(self.llvm(), subprogram.raw()) }
+    }
+
+    /// Create a file descriptor
+    pub fn file(
+        &self,
+        filename: impl AsRef<str>,
+        directory: impl AsRef<str>,
+    ) -> Result<Metadata<'a>, Error> {
+        let filename = filename.as_ref();
+        let directory = directory.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateFile(
+                self.llvm(),
+                filename.as_ptr() as *const c_char,
+                filename.len(),
+                directory.as_ptr() as *const c_char,
+                directory.len(),
+            )
+        })
+    }
+
+    /// Create a compile unit with full debug info emission, only one compile unit should be
+    /// created per builder
+    pub fn compile_unit(
+        &self,
+        lang: DWARFSourceLanguage,
+        file: Metadata<'a>,
+        producer: impl AsRef<str>,
+        optimized: bool,
+    ) -> Result<Metadata<'a>, Error> {
+        let producer = producer.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateCompileUnit(
+                self.llvm(),
+                lang,
+                file.raw(),
+                producer.as_ptr() as *const c_char,
+                producer.len(),
+                optimized as c_int,
+                cstr!("").as_ptr(),
+                0,
+                0,
+                cstr!("").as_ptr(),
+                0,
+                llvm::debuginfo::LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                0,
+                0,
+                0,
+                cstr!("").as_ptr(),
+                0,
+                cstr!("").as_ptr(),
+                0,
+            )
+        })
+    }
+
+    /// Create a subroutine type, `None` is used for a `void` return type
+    pub fn subroutine_type(
+        &self,
+        file: Metadata<'a>,
+        return_type: Option<Metadata<'a>>,
+        params: impl AsRef<[Metadata<'a>]>,
+    ) -> Result<Metadata<'a>, Error> {
+        let mut types = vec![raw_or_null(return_type)];
+        types.extend(params.as_ref().iter().map(|x| x.raw()));
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateSubroutineType(
+                self.llvm(),
+                file.raw(),
+                types.as_mut_ptr(),
+                types.len() as c_uint,
+                llvm::debuginfo::LLVMDIFlagZero,
+            )
+        })
+    }
+
+    /// Create a subprogram for a function definition, attach it using `Func::set_subprogram`
+    pub fn function(
+        &self,
+        scope: Metadata<'a>,
+        name: impl AsRef<str>,
+        file: Metadata<'a>,
+        line: usize,
+        ty: Metadata<'a>,
+        flags: DIFlags,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateFunction(
+                self.llvm(),
+                scope.raw(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                file.raw(),
+                line as c_uint,
+                ty.raw(),
+                0,
+                1,
+                line as c_uint,
+                flags,
+                0,
+            )
+        })
+    }
+
+    /// Create a lexical block
+    pub fn lexical_block(
+        &self,
+        scope: Metadata<'a>,
+        file: Metadata<'a>,
+        line: usize,
+        column: usize,
+    ) -> Result<Metadata<'a>, Error> {
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateLexicalBlock(
+                self.llvm(),
+                scope.raw(),
+                file.raw(),
+                line as c_uint,
+                column as c_uint,
+            )
+        })
+    }
+
+    /// Create a basic type
+    pub fn basic_type(
+        &self,
+        name: impl AsRef<str>,
+        size_in_bits: u64,
+        encoding: DWARFTypeEncoding,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateBasicType(
+                self.llvm(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                size_in_bits,
+                encoding,
+                llvm::debuginfo::LLVMDIFlagZero,
+            )
+        })
+    }
+
+    /// Create a pointer type
+    pub fn pointer_type(
+        &self,
+        pointee: Metadata<'a>,
+        size_in_bits: u64,
+        name: impl AsRef<str>,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreatePointerType(
+                self.llvm(),
+                pointee.raw(),
+                size_in_bits,
+                0,
+                0,
+                name.as_ptr() as *const c_char,
+                name.len(),
+            )
+        })
+    }
+
+    /// Create a struct type, `members` should be created using `DebugInfoBuilder::member_type`
+    #[allow(clippy::too_many_arguments)]
+    pub fn struct_type(
+        &self,
+        scope: Metadata<'a>,
+        name: impl AsRef<str>,
+        file: Metadata<'a>,
+        line: usize,
+        size_in_bits: u64,
+        align_in_bits: u32,
+        members: impl AsRef<[Metadata<'a>]>,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        let mut members: Vec<_> = members.as_ref().iter().map(|x| x.raw()).collect();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateStructType(
+                self.llvm(),
+                scope.raw(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                file.raw(),
+                line as c_uint,
+                size_in_bits,
+                align_in_bits,
+                llvm::debuginfo::LLVMDIFlagZero,
+                std::ptr::null_mut(),
+                members.as_mut_ptr(),
+                members.len() as c_uint,
+                0,
+                std::ptr::null_mut(),
+                name.as_ptr() as *const c_char,
+                0,
+            )
+        })
+    }
+
+    /// Create a struct member
+    #[allow(clippy::too_many_arguments)]
+    pub fn member_type(
+        &self,
+        scope: Metadata<'a>,
+        name: impl AsRef<str>,
+        file: Metadata<'a>,
+        line: usize,
+        size_in_bits: u64,
+        align_in_bits: u32,
+        offset_in_bits: u64,
+        ty: Metadata<'a>,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateMemberType(
+                self.llvm(),
+                scope.raw(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                file.raw(),
+                line as c_uint,
+                size_in_bits,
+                align_in_bits,
+                offset_in_bits,
+                llvm::debuginfo::LLVMDIFlagZero,
+                ty.raw(),
+            )
+        })
+    }
+
+    /// Create a local variable
+    pub fn auto_variable(
+        &self,
+        scope: Metadata<'a>,
+        name: impl AsRef<str>,
+        file: Metadata<'a>,
+        line: usize,
+        ty: Metadata<'a>,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateAutoVariable(
+                self.llvm(),
+                scope.raw(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                file.raw(),
+                line as c_uint,
+                ty.raw(),
+                1,
+                llvm::debuginfo::LLVMDIFlagZero,
+                0,
+            )
+        })
+    }
+
+    /// Create a function parameter variable, `arg_no` starts at 1
+    pub fn parameter_variable(
+        &self,
+        scope: Metadata<'a>,
+        name: impl AsRef<str>,
+        arg_no: usize,
+        file: Metadata<'a>,
+        line: usize,
+        ty: Metadata<'a>,
+    ) -> Result<Metadata<'a>, Error> {
+        let name = name.as_ref();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateParameterVariable(
+                self.llvm(),
+                scope.raw(),
+                name.as_ptr() as *const c_char,
+                name.len(),
+                arg_no as c_uint,
+                file.raw(),
+                line as c_uint,
+                ty.raw(),
+                1,
+                llvm::debuginfo::LLVMDIFlagZero,
+            )
+        })
+    }
+
+    /// Create a DWARF expression from a list of operations, an empty expression refers to the
+    /// value itself
+    pub fn expression(&self, ops: impl AsRef<[u64]>) -> Result<Metadata<'a>, Error> {
+        let mut ops = ops.as_ref().to_vec();
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateExpression(self.llvm(), ops.as_mut_ptr(), ops.len())
+        })
+    }
+
+    /// Create a source location
+    pub fn location(
+        &self,
+        line: usize,
+        column: usize,
+        scope: Metadata<'a>,
+    ) -> Result<Metadata<'a>, Error> {
+        self.metadata(unsafe {
+            llvm::debuginfo::LLVMDIBuilderCreateDebugLocation(
+                self.1.llvm(),
+                line as c_uint,
+                column as c_uint,
+                scope.raw(),
+                std::ptr::null_mut(),
+            )
+        })
+    }
+
+    /// Insert a `llvm.dbg.declare` call describing the variable stored at `storage`, at the end
+    /// of `bb` before any terminator
+    pub fn insert_declare_at_end(
+        &self,
+        storage: impl AsRef<Value<'a>>,
+        var: Metadata<'a>,
+        expr: Metadata<'a>,
+        loc: Metadata<'a>,
+        bb: BasicBlock<'a>,
+    ) -> Result<Instr<'a>, Error> {
+        unsafe {
+            Instr::from_inner(llvm::debuginfo::LLVMDIBuilderInsertDeclareAtEnd(
+                self.llvm(),
+                storage.as_ref().llvm(),
+                var.raw(),
+                expr.raw(),
+                loc.raw(),
+                bb.llvm(),
+            ))
+        }
+    }
+
+    /// Insert a `llvm.dbg.value` call describing the current value of a variable, at the end of
+    /// `bb` before any terminator
+    pub fn insert_dbg_value_at_end(
+        &self,
+        value: impl AsRef<Value<'a>>,
+        var: Metadata<'a>,
+        expr: Metadata<'a>,
+        loc: Metadata<'a>,
+        bb: BasicBlock<'a>,
+    ) -> Result<Instr<'a>, Error> {
+        unsafe {
+            Instr::from_inner(llvm::debuginfo::LLVMDIBuilderInsertDbgValueAtEnd(
+                self.llvm(),
+                value.as_ref().llvm(),
+                var.raw(),
+                expr.raw(),
+                loc.raw(),
+                bb.llvm(),
+            ))
+        }
+    }
+}
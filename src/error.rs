@@ -34,10 +34,34 @@ pub enum Error {
     #[error("Value is not a basic block")]
     InvalidBasicBlock,
 
+    /// Invalid LLVM global value
+    #[error("Value is not a global")]
+    InvalidGlobal,
+
     /// Invalid LLVM function
     #[error("Invalid function")]
     InvalidFunction,
 
+    /// Unknown LLVM intrinsic
+    #[error("Invalid intrinsic")]
+    InvalidIntrinsic,
+
+    /// Unknown LLVM attribute
+    #[error("Invalid attribute: {0}")]
+    InvalidAttribute(String),
+
+    /// Operation is not supported by the LLVM C API this crate is built against
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// Named struct already exists with a different body
+    #[error("Struct {0} already exists with a different body")]
+    StructMismatch(String),
+
+    /// Wrong number of values passed to `LoopBuilder::break_` or `LoopBuilder::continue_`
+    #[error("Expected {0} loop-carried values, got {1}")]
+    LoopValueCount(usize, usize),
+
     /// Module is already owned by another ExecutionEngine
     #[error("Module is already owned by another execution engine")]
     ModuleIsAlreadyOwned,
@@ -108,6 +108,7 @@ impl<'a> ExecutionEngine<'a> {
     pub unsafe fn function<T: 'a + Copy>(&self, name: impl AsRef<str>) -> Result<T, Error> {
         let name = cstr!(name.as_ref());
         let ptr = llvm::execution_engine::LLVMGetFunctionAddress(self.llvm(), name.as_ptr());
+        context::resume_callback_panic();
         Ok(std::mem::transmute_copy(&(ptr as *mut c_void)))
     }
 
@@ -128,6 +129,7 @@ impl<'a> ExecutionEngine<'a> {
     /// # Safety
     /// This function does nothing to ensure that the function actually matches the type you give
     /// it
+    #[allow(clippy::mut_from_ref)]
     pub unsafe fn global<T: 'a>(&self, global: impl AsRef<Value<'a>>) -> Result<&mut T, Error> {
         let ptr =
             llvm::execution_engine::LLVMGetPointerToGlobal(self.llvm(), global.as_ref().llvm());
@@ -157,7 +159,7 @@ impl<'a> ExecutionEngine<'a> {
     }
 
     /// Get target data
-    pub fn target_data(&self) -> Result<TargetData, Error> {
+    pub fn target_data(&self) -> Result<TargetData<'_>, Error> {
         let x = unsafe { llvm::execution_engine::LLVMGetExecutionEngineTargetData(self.llvm()) };
         TargetData::from_inner(x)
     }
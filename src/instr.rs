@@ -1,12 +1,25 @@
 use crate::*;
 
+/// Tail call marker for call instructions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TailCallKind {
+    /// No marker
+    None,
+    /// `tail`
+    Tail,
+    /// `musttail`
+    MustTail,
+    /// `notail`
+    NoTail,
+}
+
 /// Instruction value
-#[derive(Copy)]
+#[derive(Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Instr<'a>(pub(crate) Value<'a>);
 
 macro_rules! instr_type {
     ($(#[$meta:meta])* $name:ident) => {
-        #[derive(Clone, Copy)]
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
         $(#[$meta])*
         pub struct $name<'a>(pub(crate) Value<'a>);
         impl<'a> AsRef<Value<'a>> for $name<'a> {
@@ -131,7 +144,7 @@ impl<'a> Instr<'a> {
         unsafe { llvm::core::LLVMHasMetadata(self.as_ref().llvm()) == 1 }
     }
 
-    /// Get associated metadata
+    /// Get associated metadata, `id` is an ID from `Context::md_kind_id`
     pub fn get_metadata(self, id: u32) -> Result<Metadata<'a>, Error> {
         unsafe {
             Ok(Metadata(Value::from_inner(llvm::core::LLVMGetMetadata(
@@ -141,11 +154,115 @@ impl<'a> Instr<'a> {
         }
     }
 
-    /// Set associated metadata
+    /// Set associated metadata, `id` is an ID from `Context::md_kind_id`
     pub fn set_metadata(self, id: u32, meta: Metadata<'a>) {
         unsafe { llvm::core::LLVMSetMetadata(self.as_ref().llvm(), id, meta.as_ref().llvm()) }
     }
 
+    /// Get associated metadata of a fixed kind
+    pub fn get_metadata_kind(self, kind: MetadataKind) -> Result<Metadata<'a>, Error> {
+        self.get_metadata(kind.into())
+    }
+
+    /// Set associated metadata of a fixed kind
+    pub fn set_metadata_kind(self, kind: MetadataKind, meta: Metadata<'a>) {
+        self.set_metadata(kind.into(), meta)
+    }
+
+    /// Get all attached metadata as (kind ID, metadata) pairs, not including the debug location
+    pub fn all_metadata(self) -> Result<Vec<(u32, Metadata<'a>)>, Error> {
+        let ctx = self.0.into_context()?;
+        let mut len = 0;
+        let entries = unsafe {
+            llvm::core::LLVMInstructionGetAllMetadataOtherThanDebugLoc(
+                self.as_ref().llvm(),
+                &mut len,
+            )
+        };
+        let md = (0..len)
+            .map(|i| unsafe {
+                let kind = llvm::core::LLVMValueMetadataEntriesGetKind(entries, i as c_uint);
+                let md = llvm::core::LLVMValueMetadataEntriesGetMetadata(entries, i as c_uint);
+                Ok((kind, Metadata::from_raw(&ctx, md)?))
+            })
+            .collect();
+        if !entries.is_null() {
+            unsafe { llvm::core::LLVMDisposeValueMetadataEntries(entries) }
+        }
+        md
+    }
+
+    /// Attach `!range` metadata to a load or call, the value is in the half-open range `[lo, hi)`.
+    /// The bounds are truncated to the width of the instruction's type, which must be an integer,
+    /// otherwise `Error::InvalidType` is returned
+    pub fn set_range(self, lo: i64, hi: i64) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        let t = self.as_ref().type_of()?;
+        if !t.is(TypeKind::LLVMIntegerTypeKind) {
+            return Err(Error::InvalidType);
+        }
+        let md = Metadata::new_node(
+            &ctx,
+            [
+                Const::int(t, lo)?.as_ref().to_metadata(),
+                Const::int(t, hi)?.as_ref().to_metadata(),
+            ],
+        )?;
+        self.set_metadata_kind(MetadataKind::Range, md);
+        Ok(())
+    }
+
+    /// Attach `!prof` branch weights to a branch, switch or select, one weight per successor
+    pub fn set_branch_weights(self, weights: impl AsRef<[u32]>) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        let i32 = Type::i32(&ctx)?;
+        let mut md = vec![Metadata::new_string(&ctx, "branch_weights")?];
+        for w in weights.as_ref() {
+            md.push(Const::int(i32, *w as i64)?.as_ref().to_metadata());
+        }
+        self.set_metadata_kind(MetadataKind::Prof, Metadata::new_node(&ctx, md)?);
+        Ok(())
+    }
+
+    /// Attach `!nonnull` metadata to a pointer load
+    pub fn set_nonnull(self) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        self.set_metadata_kind(MetadataKind::NonNull, Metadata::new_node(&ctx, [])?);
+        Ok(())
+    }
+
+    /// Attach `!invariant.load` metadata to a load
+    pub fn set_invariant_load(self) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        self.set_metadata_kind(MetadataKind::InvariantLoad, Metadata::new_node(&ctx, [])?);
+        Ok(())
+    }
+
+    /// Attach `!alias.scope` metadata, listing the alias scopes the memory access belongs to
+    pub fn set_alias_scopes(self, scopes: impl AsRef<[Metadata<'a>]>) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        self.set_metadata_kind(MetadataKind::AliasScope, Metadata::new_node(&ctx, scopes)?);
+        Ok(())
+    }
+
+    /// Attach `!noalias` metadata, listing the alias scopes the memory access doesn't alias
+    pub fn set_noalias_scopes(self, scopes: impl AsRef<[Metadata<'a>]>) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        self.set_metadata_kind(MetadataKind::NoAlias, Metadata::new_node(&ctx, scopes)?);
+        Ok(())
+    }
+
+    /// Attach `!nontemporal` metadata to a load or store
+    pub fn set_nontemporal(self) -> Result<(), Error> {
+        let ctx = self.0.into_context()?;
+        let one = Const::int(Type::i32(&ctx)?, 1)?;
+        self.set_metadata_kind(
+            MetadataKind::NonTemporal,
+            Metadata::new_node(&ctx, [one.as_ref().to_metadata()])?,
+        );
+        Ok(())
+    }
+
     /// Get number of successors
     pub fn num_successors(self) -> usize {
         unsafe { llvm::core::LLVMGetNumSuccessors(self.as_ref().llvm()) as usize }
@@ -286,6 +403,113 @@ impl<'a> InstrCall<'a> {
     pub fn set_tail_call(&mut self, b: bool) {
         unsafe { llvm::core::LLVMSetTailCall(self.as_ref().llvm(), if b { 1 } else { 0 }) }
     }
+
+    /// Get the tail call kind
+    pub fn tail_call_kind(self) -> TailCallKind {
+        match unsafe { llama_get_tail_call_kind(self.as_ref().llvm()) } {
+            1 => TailCallKind::Tail,
+            2 => TailCallKind::MustTail,
+            3 => TailCallKind::NoTail,
+            _ => TailCallKind::None,
+        }
+    }
+
+    /// Set the tail call kind
+    pub fn set_tail_call_kind(&mut self, kind: TailCallKind) {
+        let kind = match kind {
+            TailCallKind::None => 0,
+            TailCallKind::Tail => 1,
+            TailCallKind::MustTail => 2,
+            TailCallKind::NoTail => 3,
+        };
+        unsafe { llama_set_tail_call_kind(self.as_ref().llvm(), kind) }
+    }
+
+    /// Get the called value, this is the same as `InstrCall::value`
+    pub fn called_value(self) -> Result<Value<'a>, Error> {
+        self.value()
+    }
+
+    /// Get the arguments passed to the call
+    pub fn arg_operands(self) -> Result<Vec<Value<'a>>, Error> {
+        (0..self.num_operands())
+            .map(|i| unsafe {
+                Value::from_inner(llvm::core::LLVMGetOperand(
+                    self.as_ref().llvm(),
+                    i as c_uint,
+                ))
+            })
+            .collect()
+    }
+
+    /// Get calling convention ID, this may be a target-specific convention that isn't listed in
+    /// `CallConv`
+    pub fn call_conv(self) -> u32 {
+        unsafe { llvm::core::LLVMGetInstructionCallConv(self.as_ref().llvm()) }
+    }
+
+    /// Set calling convention, this should match the calling convention of the called function
+    pub fn set_call_conv(&mut self, conv: CallConv) {
+        unsafe { llvm::core::LLVMSetInstructionCallConv(self.as_ref().llvm(), conv as u32) }
+    }
+
+    /// Set call-site attribute
+    pub fn add_attribute(&mut self, index: AttributeIndex, attr: &Attribute<'a>) {
+        unsafe {
+            llvm::core::LLVMAddCallSiteAttribute(
+                self.as_ref().llvm(),
+                index.get_index(),
+                attr.llvm(),
+            )
+        }
+    }
+
+    /// Remove a call-site enum attribute
+    pub fn remove_enum_attribute(&mut self, index: AttributeIndex, kind_id: u32) {
+        unsafe {
+            llvm::core::LLVMRemoveCallSiteEnumAttribute(
+                self.as_ref().llvm(),
+                index.get_index(),
+                kind_id,
+            )
+        }
+    }
+
+    /// Remove a call-site string attribute
+    pub fn remove_string_attribute(&mut self, index: AttributeIndex, k: impl AsRef<str>) {
+        let len = k.as_ref().len();
+        let k = cstr!(k.as_ref());
+        unsafe {
+            llvm::core::LLVMRemoveCallSiteStringAttribute(
+                self.as_ref().llvm(),
+                index.get_index(),
+                k.as_ptr(),
+                len as u32,
+            )
+        }
+    }
+
+    /// Get all call-site attributes
+    pub fn attributes(self, index: AttributeIndex) -> Vec<Attribute<'a>> {
+        let count = unsafe {
+            llvm::core::LLVMGetCallSiteAttributeCount(self.as_ref().llvm(), index.get_index())
+        };
+
+        let mut output = vec![std::ptr::null_mut(); count as usize];
+
+        unsafe {
+            llvm::core::LLVMGetCallSiteAttributes(
+                self.as_ref().llvm(),
+                index.get_index(),
+                output.as_mut_ptr(),
+            );
+        }
+
+        output
+            .into_iter()
+            .map(|x| Attribute::from_inner(x).unwrap())
+            .collect()
+    }
 }
 
 impl<'a> InstrSwitch<'a> {
@@ -311,6 +535,7 @@ impl<'a> InstrIndirectBr<'a> {
     }
 }
 
+#[allow(clippy::non_canonical_clone_impl)]
 impl<'a> Clone for Instr<'a> {
     fn clone(&self) -> Instr<'a> {
         unsafe {
@@ -95,6 +95,14 @@ macro_rules! instr {
 
 extern "C" {
     fn strlen(_: *const std::os::raw::c_char) -> usize;
+    fn llama_const_data_array(
+        element_type: *mut llvm::LLVMType,
+        data: *const std::os::raw::c_char,
+        len: usize,
+        num_elements: u64,
+    ) -> *mut llvm::LLVMValue;
+    fn llama_get_tail_call_kind(call: *mut llvm::LLVMValue) -> std::os::raw::c_uint;
+    fn llama_set_tail_call_kind(call: *mut llvm::LLVMValue, kind: std::os::raw::c_uint);
 }
 
 mod attribute;
@@ -102,18 +110,23 @@ mod basic_block;
 mod binary;
 mod builder;
 mod codegen;
+mod comdat;
 mod r#const;
 mod context;
+mod debug_info;
 mod error;
 mod execution_engine;
 mod instr;
 mod jit;
+mod loop_hints;
 mod memory_buffer;
 mod message;
 mod metadata;
 mod module;
 mod pass_manager;
+mod ssa;
 mod target;
+mod tbaa;
 mod r#type;
 mod value;
 
@@ -125,27 +138,35 @@ pub(crate) use std::ptr::NonNull;
 /// Re-export `llvm_sys` to provide access to any missing functionality
 pub use llvm_sys as llvm;
 
-pub use crate::attribute::Attribute;
+pub use crate::attribute::{AttrKind, Attribute};
 pub use crate::basic_block::BasicBlock;
 pub use crate::binary::Binary;
-pub use crate::builder::Builder;
+pub use crate::builder::{Builder, LoopBuilder};
 pub use crate::codegen::Codegen;
-pub use crate::context::Context;
+pub use crate::comdat::{Comdat, ComdatSelectionKind};
+pub use crate::context::{Context, Diagnostic, Diagnostics};
+pub use crate::debug_info::{
+    DIFlags, DWARFSourceLanguage, DWARFTypeEncoding, DebugInfoBuilder, DW_ATE_BOOLEAN,
+    DW_ATE_FLOAT, DW_ATE_SIGNED, DW_ATE_SIGNED_CHAR, DW_ATE_UNSIGNED, DW_ATE_UNSIGNED_CHAR,
+};
 pub use crate::error::Error;
 pub use crate::execution_engine::ExecutionEngine;
 pub use crate::instr::*;
 pub use crate::jit::Jit;
+pub use crate::loop_hints::LoopHints;
 pub use crate::memory_buffer::MemoryBuffer;
 pub use crate::message::Message;
-pub use crate::metadata::Metadata;
+pub use crate::metadata::{Metadata, MetadataKind};
 pub use crate::module::Module;
 pub use crate::pass_manager::{
     transforms, FuncPassManager, ModulePassManager, PassManager, Transform,
 };
-pub use crate::r#const::Const;
-pub use crate::r#type::{FuncType, StructType, Type, TypeKind};
+pub use crate::r#const::{Const, ConstData, ConstValue, FromConst, Relocation};
+pub use crate::r#type::{FloatKind, FuncType, LLVMType, StructType, Type, TypeDesc, TypeKind};
+pub use crate::ssa::Variable;
 pub use crate::target::{Target, TargetData, TargetMachine};
-pub use crate::value::{AttributeIndex, Func, Value, ValueKind};
+pub use crate::tbaa::TbaaBuilder;
+pub use crate::value::{AttributeIndex, Func, GlobalAlias, GlobalIFunc, Value, ValueKind};
 
 pub use llvm::{
     object::LLVMBinaryType as BinaryType,
@@ -155,11 +176,11 @@ pub use llvm::{
         LLVMRelocMode as RelocMode,
     },
     LLVMAtomicOrdering as AtomicOrdering, LLVMAtomicRMWBinOp as AtomicRMWBinOp,
-    LLVMCallConv as CallConv, LLVMDiagnosticSeverity as DiagnosticSeverity,
-    LLVMInlineAsmDialect as InlineAsmDialect, LLVMIntPredicate as Icmp, LLVMLinkage as Linkage,
-    LLVMModuleFlagBehavior as ModuleFlagBehavior, LLVMOpcode as OpCode, LLVMRealPredicate as Fcmp,
-    LLVMThreadLocalMode as ThreadLocalMode, LLVMUnnamedAddr as UnnamedAddr,
-    LLVMVisibility as Visibility,
+    LLVMCallConv as CallConv, LLVMDLLStorageClass as DLLStorageClass,
+    LLVMDiagnosticSeverity as DiagnosticSeverity, LLVMInlineAsmDialect as InlineAsmDialect,
+    LLVMIntPredicate as Icmp, LLVMLinkage as Linkage, LLVMModuleFlagBehavior as ModuleFlagBehavior,
+    LLVMOpcode as OpCode, LLVMRealPredicate as Fcmp, LLVMThreadLocalMode as ThreadLocalMode,
+    LLVMUnnamedAddr as UnnamedAddr, LLVMVisibility as Visibility,
 };
 
 /// Allows for llama types to be converted into LLVM pointers
@@ -0,0 +1,122 @@
+use crate::*;
+
+/// `LoopHints` describes `!llvm.loop` metadata used to guide loop transformations
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoopHints {
+    unroll: Option<bool>,
+    unroll_count: Option<u32>,
+    vectorize: Option<bool>,
+    vectorize_width: Option<u32>,
+    interleave_count: Option<u32>,
+    distribute: Option<bool>,
+    must_progress: bool,
+}
+
+impl LoopHints {
+    /// Create an empty set of hints
+    pub fn new() -> LoopHints {
+        LoopHints::default()
+    }
+
+    /// Enable or disable unrolling
+    pub fn unroll(mut self, enable: bool) -> LoopHints {
+        self.unroll = Some(enable);
+        self
+    }
+
+    /// Set the unroll count
+    pub fn unroll_count(mut self, count: u32) -> LoopHints {
+        self.unroll_count = Some(count);
+        self
+    }
+
+    /// Enable or disable vectorization
+    pub fn vectorize(mut self, enable: bool) -> LoopHints {
+        self.vectorize = Some(enable);
+        self
+    }
+
+    /// Set the vectorization width
+    pub fn vectorize_width(mut self, width: u32) -> LoopHints {
+        self.vectorize_width = Some(width);
+        self
+    }
+
+    /// Set the interleave count
+    pub fn interleave_count(mut self, count: u32) -> LoopHints {
+        self.interleave_count = Some(count);
+        self
+    }
+
+    /// Enable or disable loop distribution
+    pub fn distribute(mut self, enable: bool) -> LoopHints {
+        self.distribute = Some(enable);
+        self
+    }
+
+    /// Mark the loop as required to make forward progress
+    pub fn must_progress(mut self) -> LoopHints {
+        self.must_progress = true;
+        self
+    }
+
+    /// Build the self-referential `!llvm.loop` node
+    pub fn to_metadata<'a>(&self, ctx: &Context<'a>) -> Result<Metadata<'a>, Error> {
+        let i1 = Type::i1(ctx)?;
+        let i32 = Type::i32(ctx)?;
+
+        let flag = |name: &str| Metadata::new_node(ctx, [Metadata::new_string(ctx, name)?]);
+        let bool = |name: &str, b: bool| {
+            Metadata::new_node(
+                ctx,
+                [
+                    Metadata::new_string(ctx, name)?,
+                    Const::int(i1, b as i64)?.as_ref().to_metadata(),
+                ],
+            )
+        };
+        let int = |name: &str, n: u32| {
+            Metadata::new_node(
+                ctx,
+                [
+                    Metadata::new_string(ctx, name)?,
+                    Const::int(i32, n as i64)?.as_ref().to_metadata(),
+                ],
+            )
+        };
+
+        let mut md = Vec::new();
+        if self.must_progress {
+            md.push(flag("llvm.loop.mustprogress")?);
+        }
+        match self.unroll {
+            Some(true) => md.push(flag("llvm.loop.unroll.enable")?),
+            Some(false) => md.push(flag("llvm.loop.unroll.disable")?),
+            None => (),
+        }
+        if let Some(n) = self.unroll_count {
+            md.push(int("llvm.loop.unroll.count", n)?);
+        }
+        if let Some(b) = self.vectorize {
+            md.push(bool("llvm.loop.vectorize.enable", b)?);
+        }
+        if let Some(n) = self.vectorize_width {
+            md.push(int("llvm.loop.vectorize.width", n)?);
+        }
+        if let Some(n) = self.interleave_count {
+            md.push(int("llvm.loop.interleave.count", n)?);
+        }
+        if let Some(b) = self.distribute {
+            md.push(bool("llvm.loop.distribute.enable", b)?);
+        }
+
+        Metadata::new_self_referential_node(ctx, md)
+    }
+
+    /// Attach the hints to the branch at the end of a loop latch
+    pub fn attach(&self, latch: Instr<'_>) -> Result<(), Error> {
+        let ctx = latch.as_ref().into_context()?;
+        latch.set_metadata_kind(MetadataKind::Loop, self.to_metadata(&ctx)?);
+        Ok(())
+    }
+}
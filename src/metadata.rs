@@ -1,7 +1,7 @@
 use crate::*;
 
 /// Metadata values
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Metadata<'a>(pub(crate) Value<'a>);
 
 impl<'a> AsRef<Value<'a>> for Metadata<'a> {
@@ -16,7 +16,94 @@ impl<'a> From<Metadata<'a>> for Value<'a> {
     }
 }
 
+/// Fixed metadata kinds that are always registered with the same ID
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum MetadataKind {
+    /// `!dbg`
+    Dbg = 0,
+    /// `!tbaa`
+    Tbaa = 1,
+    /// `!prof`
+    Prof = 2,
+    /// `!fpmath`
+    FpMath = 3,
+    /// `!range`
+    Range = 4,
+    /// `!tbaa.struct`
+    TbaaStruct = 5,
+    /// `!invariant.load`
+    InvariantLoad = 6,
+    /// `!alias.scope`
+    AliasScope = 7,
+    /// `!noalias`
+    NoAlias = 8,
+    /// `!nontemporal`
+    NonTemporal = 9,
+    /// `!llvm.mem.parallel_loop_access`
+    MemParallelLoopAccess = 10,
+    /// `!nonnull`
+    NonNull = 11,
+    /// `!dereferenceable`
+    Dereferenceable = 12,
+    /// `!dereferenceable_or_null`
+    DereferenceableOrNull = 13,
+    /// `!align`
+    Align = 17,
+    /// `!llvm.loop`
+    Loop = 18,
+    /// `!llvm.access.group`
+    AccessGroup = 25,
+}
+
+impl MetadataKind {
+    /// Get the name of the metadata kind
+    pub fn name(self) -> &'static str {
+        match self {
+            MetadataKind::Dbg => "dbg",
+            MetadataKind::Tbaa => "tbaa",
+            MetadataKind::Prof => "prof",
+            MetadataKind::FpMath => "fpmath",
+            MetadataKind::Range => "range",
+            MetadataKind::TbaaStruct => "tbaa.struct",
+            MetadataKind::InvariantLoad => "invariant.load",
+            MetadataKind::AliasScope => "alias.scope",
+            MetadataKind::NoAlias => "noalias",
+            MetadataKind::NonTemporal => "nontemporal",
+            MetadataKind::MemParallelLoopAccess => "llvm.mem.parallel_loop_access",
+            MetadataKind::NonNull => "nonnull",
+            MetadataKind::Dereferenceable => "dereferenceable",
+            MetadataKind::DereferenceableOrNull => "dereferenceable_or_null",
+            MetadataKind::Align => "align",
+            MetadataKind::Loop => "llvm.loop",
+            MetadataKind::AccessGroup => "llvm.access.group",
+        }
+    }
+}
+
+impl From<MetadataKind> for u32 {
+    fn from(x: MetadataKind) -> u32 {
+        x as u32
+    }
+}
+
 impl<'a> Metadata<'a> {
+    pub(crate) fn from_raw(
+        ctx: &Context<'a>,
+        md: *mut llvm::LLVMOpaqueMetadata,
+    ) -> Result<Metadata<'a>, Error> {
+        wrap_inner(md)?;
+        unsafe {
+            Ok(Metadata(Value::from_inner(
+                llvm::core::LLVMMetadataAsValue(ctx.llvm(), md),
+            )?))
+        }
+    }
+
+    pub(crate) fn raw(self) -> *mut llvm::LLVMOpaqueMetadata {
+        unsafe { llvm::core::LLVMValueAsMetadata(self.as_ref().llvm()) }
+    }
+
     /// Create a string attribute
     pub fn new_string(ctx: &Context<'a>, k: impl AsRef<str>) -> Result<Metadata<'a>, Error> {
         let k = k.as_ref();
@@ -54,6 +141,39 @@ impl<'a> Metadata<'a> {
         }
     }
 
+    /// Create a node with a reference to itself as the first operand followed by `mds`, this is
+    /// the form used by `!llvm.loop` and alias scope metadata. The node is created uniqued, LLVM
+    /// only makes it distinct once the self-reference is resolved, so every call returns a new
+    /// node
+    pub fn new_self_referential_node(
+        ctx: &Context<'a>,
+        mds: impl AsRef<[Metadata<'a>]>,
+    ) -> Result<Metadata<'a>, Error> {
+        unsafe {
+            let tmp = llvm::debuginfo::LLVMTemporaryMDNode(ctx.llvm(), std::ptr::null_mut(), 0);
+            let mut ptr = vec![tmp];
+            ptr.extend(mds.as_ref().iter().map(|x| x.raw()));
+            let node = llvm::core::LLVMMDNodeInContext2(ctx.llvm(), ptr.as_mut_ptr(), ptr.len());
+            // This also deletes `tmp`, so it must not be passed to `LLVMDisposeTemporaryMDNode`
+            llvm::debuginfo::LLVMMetadataReplaceAllUsesWith(tmp, node);
+            Metadata::from_raw(ctx, node)
+        }
+    }
+
+    /// Create a new alias scope domain
+    pub fn alias_domain(ctx: &Context<'a>, name: impl AsRef<str>) -> Result<Metadata<'a>, Error> {
+        Metadata::new_self_referential_node(ctx, [Metadata::new_string(ctx, name)?])
+    }
+
+    /// Create a new alias scope inside of `domain`
+    pub fn alias_scope(
+        ctx: &Context<'a>,
+        domain: Metadata<'a>,
+        name: impl AsRef<str>,
+    ) -> Result<Metadata<'a>, Error> {
+        Metadata::new_self_referential_node(ctx, [domain, Metadata::new_string(ctx, name)?])
+    }
+
     /// Get string value
     pub fn as_str(self) -> Result<&'a str, Error> {
         unsafe {
@@ -256,6 +256,197 @@ impl<'a> Module<'a> {
         Ok(Func(Value::from_inner(value)?))
     }
 
+    /// Get or insert the declaration of an intrinsic, `types` is used to select an overload
+    pub fn intrinsic(
+        &self,
+        name: impl AsRef<str>,
+        types: impl AsRef<[Type<'a>]>,
+    ) -> Result<Func<'a>, Error> {
+        let len = name.as_ref().len();
+        let name = cstr!(name.as_ref());
+        let id = unsafe { llvm::core::LLVMLookupIntrinsicID(name.as_ptr(), len) };
+        if id == 0 {
+            return Err(Error::InvalidIntrinsic);
+        }
+
+        let mut types: Vec<*mut llvm::LLVMType> = types.as_ref().iter().map(|x| x.llvm()).collect();
+        let value = unsafe {
+            llvm::core::LLVMGetIntrinsicDeclaration(
+                self.llvm(),
+                id,
+                types.as_mut_ptr(),
+                types.len(),
+            )
+        };
+        Ok(Func(Value::from_inner(value)?))
+    }
+
+    /// Get a comdat by name, creating it if it doesn't exist
+    pub fn get_or_insert_comdat(&self, name: impl AsRef<str>) -> Result<Comdat<'a>, Error> {
+        let name = cstr!(name.as_ref());
+        unsafe {
+            Comdat::from_inner(llvm::comdat::LLVMGetOrInsertComdat(
+                self.llvm(),
+                name.as_ptr(),
+            ))
+        }
+    }
+
+    /// Create a new alias to `aliasee`, `ty` is the type of the aliased value. `aliasee` must be a
+    /// pointer, otherwise `Error::InvalidType` is returned
+    pub fn add_alias(
+        &self,
+        name: impl AsRef<str>,
+        ty: impl AsRef<Type<'a>>,
+        aliasee: impl AsRef<Value<'a>>,
+    ) -> Result<GlobalAlias<'a>, Error> {
+        let name = cstr!(name.as_ref());
+        let t = aliasee.as_ref().type_of()?;
+        if !t.is(TypeKind::LLVMPointerTypeKind) {
+            return Err(Error::InvalidType);
+        }
+        let addr = t.pointer_address_space();
+        let value = unsafe {
+            llvm::core::LLVMAddAlias2(
+                self.llvm(),
+                ty.as_ref().llvm(),
+                addr as c_uint,
+                aliasee.as_ref().llvm(),
+                name.as_ptr(),
+            )
+        };
+        Value::from_inner(value).map(GlobalAlias)
+    }
+
+    /// Get an alias by name
+    pub fn alias(&self, name: impl AsRef<str>) -> Result<GlobalAlias<'a>, Error> {
+        let len = name.as_ref().len();
+        let name = cstr!(name.as_ref());
+        let value = unsafe { llvm::core::LLVMGetNamedGlobalAlias(self.llvm(), name.as_ptr(), len) };
+        Value::from_inner(value).map(GlobalAlias)
+    }
+
+    /// Get the first alias
+    pub fn first_alias(&self) -> Result<GlobalAlias<'a>, Error> {
+        let value = unsafe { llvm::core::LLVMGetFirstGlobalAlias(self.llvm()) };
+        Value::from_inner(value).map(GlobalAlias)
+    }
+
+    /// Create a new ifunc with the given type, `resolver` should return a pointer to the
+    /// implementation
+    pub fn add_ifunc(
+        &self,
+        name: impl AsRef<str>,
+        ft: FuncType<'a>,
+        resolver: Func<'a>,
+    ) -> Result<GlobalIFunc<'a>, Error> {
+        let len = name.as_ref().len();
+        let name = cstr!(name.as_ref());
+        let value = unsafe {
+            llvm::core::LLVMAddGlobalIFunc(
+                self.llvm(),
+                name.as_ptr(),
+                len,
+                ft.as_ref().llvm(),
+                0,
+                resolver.as_ref().llvm(),
+            )
+        };
+        Value::from_inner(value).map(GlobalIFunc)
+    }
+
+    /// Get an ifunc by name
+    pub fn ifunc(&self, name: impl AsRef<str>) -> Result<GlobalIFunc<'a>, Error> {
+        let len = name.as_ref().len();
+        let name = cstr!(name.as_ref());
+        let value = unsafe { llvm::core::LLVMGetNamedGlobalIFunc(self.llvm(), name.as_ptr(), len) };
+        Value::from_inner(value).map(GlobalIFunc)
+    }
+
+    /// Get the first ifunc
+    pub fn first_ifunc(&self) -> Result<GlobalIFunc<'a>, Error> {
+        let value = unsafe { llvm::core::LLVMGetFirstGlobalIFunc(self.llvm()) };
+        Value::from_inner(value).map(GlobalIFunc)
+    }
+
+    /// Append a metadata node to the named metadata `name`, creating it if needed
+    pub fn add_named_metadata_operand(&self, name: impl AsRef<str>, node: Metadata<'a>) {
+        let name = cstr!(name.as_ref());
+        unsafe {
+            llvm::core::LLVMAddNamedMetadataOperand(
+                self.llvm(),
+                name.as_ptr(),
+                node.as_ref().llvm(),
+            )
+        }
+    }
+
+    /// Get the operands of the named metadata `name`, this is empty if it doesn't exist
+    pub fn named_metadata(&self, name: impl AsRef<str>) -> Result<Vec<Metadata<'a>>, Error> {
+        let name = cstr!(name.as_ref());
+        let len =
+            unsafe { llvm::core::LLVMGetNamedMetadataNumOperands(self.llvm(), name.as_ptr()) };
+        let mut data = vec![std::ptr::null_mut(); len as usize];
+        unsafe {
+            llvm::core::LLVMGetNamedMetadataOperands(self.llvm(), name.as_ptr(), data.as_mut_ptr())
+        }
+        data.into_iter()
+            .map(|x| Ok(Metadata(Value::from_inner(x)?)))
+            .collect()
+    }
+
+    /// Add a module flag
+    pub fn add_module_flag(
+        &self,
+        behavior: ModuleFlagBehavior,
+        key: impl AsRef<str>,
+        value: Metadata<'a>,
+    ) {
+        let key = key.as_ref();
+        unsafe {
+            llvm::core::LLVMAddModuleFlag(
+                self.llvm(),
+                behavior,
+                key.as_ptr() as *const c_char,
+                key.len(),
+                value.raw(),
+            )
+        }
+    }
+
+    /// Get a module flag by key
+    pub fn module_flag(&self, key: impl AsRef<str>) -> Result<Metadata<'a>, Error> {
+        let key = key.as_ref();
+        let md = unsafe {
+            llvm::core::LLVMGetModuleFlag(self.llvm(), key.as_ptr() as *const c_char, key.len())
+        };
+        Metadata::from_raw(&self.context()?, md)
+    }
+
+    /// Get all module flags
+    pub fn module_flags(&self) -> Result<Vec<(ModuleFlagBehavior, String, Metadata<'a>)>, Error> {
+        let ctx = self.context()?;
+        let mut len = 0;
+        let entries = unsafe { llvm::core::LLVMCopyModuleFlagsMetadata(self.llvm(), &mut len) };
+        let flags = (0..len)
+            .map(|i| unsafe {
+                let behavior =
+                    llvm::core::LLVMModuleFlagEntriesGetFlagBehavior(entries, i as c_uint);
+                let mut key_len = 0;
+                let key =
+                    llvm::core::LLVMModuleFlagEntriesGetKey(entries, i as c_uint, &mut key_len);
+                let key = std::slice::from_raw_parts(key as *const u8, key_len);
+                let key = std::str::from_utf8(key)?.to_string();
+                let md = llvm::core::LLVMModuleFlagEntriesGetMetadata(entries, i as c_uint);
+                Ok((behavior, key, Metadata::from_raw(&ctx, md)?))
+            })
+            .collect();
+        if !entries.is_null() {
+            unsafe { llvm::core::LLVMDisposeModuleFlagsMetadata(entries) }
+        }
+        flags
+    }
+
     /// Get a global value by name
     pub fn global(&self, name: impl AsRef<str>) -> Result<Value<'a>, Error> {
         let name = cstr!(name.as_ref());
@@ -366,10 +557,12 @@ impl<'a> Module<'a> {
 
     /// Link another module into `self`
     pub fn link(&self, other: &Module) -> bool {
-        unsafe {
+        let r = unsafe {
             let other = llvm::core::LLVMCloneModule(other.llvm());
-            llvm::linker::LLVMLinkModules2(self.llvm(), other) == 1
-        }
+            llvm::linker::LLVMLinkModules2(self.llvm(), other)
+        };
+        context::resume_callback_panic();
+        r == 1
     }
 
     /// Set WASM32 target/data layout
@@ -384,7 +577,7 @@ impl<'a> Module<'a> {
     }
 
     /// Get target data
-    pub fn target_data(&self) -> Result<TargetData, Error> {
+    pub fn target_data(&self) -> Result<TargetData<'_>, Error> {
         let x = unsafe { llvm::target::LLVMGetModuleDataLayout(self.llvm()) };
         TargetData::from_inner(x)
     }
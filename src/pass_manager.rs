@@ -68,7 +68,9 @@ impl<'a> PassManager for FuncPassManager<'a> {
     type Kind = Func<'a>;
 
     fn run(&self, f: &Func<'a>) -> bool {
-        unsafe { llvm::core::LLVMRunFunctionPassManager(self.llvm(), f.as_ref().llvm()) == 1 }
+        let r = unsafe { llvm::core::LLVMRunFunctionPassManager(self.llvm(), f.as_ref().llvm()) };
+        context::resume_callback_panic();
+        r == 1
     }
 }
 
@@ -76,6 +78,8 @@ impl<'a> PassManager for ModulePassManager<'a> {
     type Kind = Module<'a>;
 
     fn run(&self, module: &Module<'a>) -> bool {
-        unsafe { llvm::core::LLVMRunPassManager(self.llvm(), module.llvm()) == 1 }
+        let r = unsafe { llvm::core::LLVMRunPassManager(self.llvm(), module.llvm()) };
+        context::resume_callback_panic();
+        r == 1
     }
 }
@@ -0,0 +1,224 @@
+use crate::*;
+
+use std::collections::{HashMap, HashSet};
+
+/// Mutable local variable, see `Builder::declare_var`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Variable(usize);
+
+type Block = *mut llvm::LLVMBasicBlock;
+
+/// On-the-fly SSA construction state, based on "Simple and Efficient Construction of Static Single
+/// Assignment Form" by Braun et al.
+///
+/// Definitions are tracked for one function at a time, using a block from another function
+/// clears everything except the declared variables
+#[derive(Default)]
+pub(crate) struct Ssa<'a> {
+    types: Vec<Type<'a>>,
+    function: Option<*mut llvm::LLVMValue>,
+    defs: HashMap<(Variable, Block), Value<'a>>,
+    incomplete_phis: HashMap<Block, Vec<(Variable, InstrPhi<'a>)>>,
+    sealed: HashSet<Block>,
+    filling: HashSet<*mut llvm::LLVMValue>,
+    phi_builder: Option<Box<Builder<'a>>>,
+}
+
+fn phi_users<'a>(phi: InstrPhi<'a>) -> Vec<InstrPhi<'a>> {
+    let mut users = Vec::new();
+    unsafe {
+        let mut u = llvm::core::LLVMGetFirstUse(phi.as_ref().llvm());
+        while !u.is_null() {
+            let user = llvm::core::LLVMGetUser(u);
+            if user != phi.as_ref().llvm() && !llvm::core::LLVMIsAPHINode(user).is_null() {
+                if let Ok(user) = Instr::from_inner(user) {
+                    users.push(InstrPhi::from_instr(user));
+                }
+            }
+            u = llvm::core::LLVMGetNextUse(u);
+        }
+    }
+    users
+}
+
+impl<'a> Ssa<'a> {
+    pub(crate) fn declare(&mut self, ty: Type<'a>) -> Variable {
+        self.types.push(ty);
+        Variable(self.types.len() - 1)
+    }
+
+    fn enter(&mut self, bb: BasicBlock<'a>) -> Result<(), Error> {
+        let f = bb.parent()?.llvm();
+        if self.function != Some(f) {
+            self.defs.clear();
+            self.incomplete_phis.clear();
+            self.sealed.clear();
+            self.filling.clear();
+            self.function = Some(f);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write(
+        &mut self,
+        var: Variable,
+        bb: BasicBlock<'a>,
+        value: Value<'a>,
+    ) -> Result<(), Error> {
+        self.enter(bb)?;
+        self.define(var, bb, value);
+        Ok(())
+    }
+
+    pub(crate) fn read(
+        &mut self,
+        ctx: &Context<'a>,
+        var: Variable,
+        bb: BasicBlock<'a>,
+    ) -> Result<Value<'a>, Error> {
+        self.enter(bb)?;
+        self.lookup(ctx, var, bb)
+    }
+
+    fn define(&mut self, var: Variable, bb: BasicBlock<'a>, value: Value<'a>) {
+        self.defs.insert((var, bb.llvm()), value);
+    }
+
+    fn lookup(
+        &mut self,
+        ctx: &Context<'a>,
+        var: Variable,
+        bb: BasicBlock<'a>,
+    ) -> Result<Value<'a>, Error> {
+        match self.defs.get(&(var, bb.llvm())) {
+            Some(v) => Ok(*v),
+            None => self.lookup_recursive(ctx, var, bb),
+        }
+    }
+
+    fn lookup_recursive(
+        &mut self,
+        ctx: &Context<'a>,
+        var: Variable,
+        bb: BasicBlock<'a>,
+    ) -> Result<Value<'a>, Error> {
+        let value = if !self.sealed.contains(&bb.llvm()) {
+            let phi = self.new_phi(ctx, var, bb)?;
+            self.incomplete_phis
+                .entry(bb.llvm())
+                .or_default()
+                .push((var, phi));
+            phi.0
+        } else {
+            let preds = bb.predecessors();
+            if preds.len() == 1 {
+                self.lookup(ctx, var, preds[0])?
+            } else {
+                let phi = self.new_phi(ctx, var, bb)?;
+                self.define(var, bb, phi.0);
+                self.add_phi_operands(ctx, var, phi)?
+            }
+        };
+
+        self.define(var, bb, value);
+        Ok(value)
+    }
+
+    fn new_phi(
+        &mut self,
+        ctx: &Context<'a>,
+        var: Variable,
+        bb: BasicBlock<'a>,
+    ) -> Result<InstrPhi<'a>, Error> {
+        let build = match &self.phi_builder {
+            Some(build) => build,
+            None => self.phi_builder.insert(Box::new(Builder::new(ctx)?)),
+        };
+        match bb.first_instruction() {
+            Ok(i) => build.position_before(Instr(i)),
+            Err(_) => build.position_at_end(bb),
+        }
+        build.phi(self.types[var.0], "")
+    }
+
+    fn add_phi_operands(
+        &mut self,
+        ctx: &Context<'a>,
+        var: Variable,
+        mut phi: InstrPhi<'a>,
+    ) -> Result<Value<'a>, Error> {
+        self.filling.insert(phi.as_ref().llvm());
+        let res = phi.to_instr().parent().and_then(|bb| {
+            for pred in bb.predecessors() {
+                let v = self.lookup(ctx, var, pred)?;
+                phi.add_incoming([(v, pred)]);
+            }
+            Ok(())
+        });
+        self.filling.remove(&phi.as_ref().llvm());
+        res?;
+        self.try_remove_trivial_phi(phi, &mut HashSet::new())
+    }
+
+    /// `removed` only lives for a single pass, no phi nodes are created while it is in use so the
+    /// addresses of deleted phi nodes can't be reused by new ones
+    fn try_remove_trivial_phi(
+        &mut self,
+        phi: InstrPhi<'a>,
+        removed: &mut HashSet<*mut llvm::LLVMValue>,
+    ) -> Result<Value<'a>, Error> {
+        let mut same: Option<Value<'a>> = None;
+        for i in 0..phi.count_incoming() {
+            let op = phi.incoming_value(i)?;
+            if op.llvm() == phi.as_ref().llvm() || same.map(|x| x.llvm()) == Some(op.llvm()) {
+                continue;
+            }
+
+            if same.is_some() {
+                return Ok(phi.0);
+            }
+
+            same = Some(op);
+        }
+
+        let same = match same {
+            Some(x) => x,
+            None => Const::undef(phi.as_ref().type_of()?)?.0,
+        };
+
+        let users = phi_users(phi);
+        phi.as_ref().replace_all_uses_with(same);
+        for v in self.defs.values_mut() {
+            if v.llvm() == phi.as_ref().llvm() {
+                *v = same;
+            }
+        }
+        removed.insert(phi.as_ref().llvm());
+        phi.to_instr().delete();
+
+        for user in users {
+            let ptr = user.as_ref().llvm();
+            if !removed.contains(&ptr) && !self.filling.contains(&ptr) {
+                self.try_remove_trivial_phi(user, removed)?;
+            }
+        }
+
+        Ok(same)
+    }
+
+    pub(crate) fn seal(&mut self, ctx: &Context<'a>, bb: BasicBlock<'a>) -> Result<(), Error> {
+        self.enter(bb)?;
+        if self.sealed.contains(&bb.llvm()) {
+            return Ok(());
+        }
+
+        if let Some(phis) = self.incomplete_phis.remove(&bb.llvm()) {
+            for (var, phi) in phis {
+                self.add_phi_operands(ctx, var, phi)?;
+            }
+        }
+
+        self.sealed.insert(bb.llvm());
+        Ok(())
+    }
+}
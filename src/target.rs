@@ -134,8 +134,8 @@ impl<'a> TargetData<'a> {
 /// LLVMTarget wrapper
 pub struct Target(llvm::target_machine::LLVMTargetRef);
 
-impl<'a> Target {
-    /// Get target from triple
+impl Target {
+    /// Get target from name
     pub fn new(s: impl AsRef<str>) -> Result<Target, Error> {
         let s = cstr!(s.as_ref());
         unsafe {
@@ -147,9 +147,30 @@ impl<'a> Target {
         }
     }
 
+    /// Get target from triple
+    pub fn from_triple(triple: impl AsRef<str>) -> Result<Target, Error> {
+        let triple = cstr!(triple.as_ref());
+        let mut target = std::ptr::null_mut();
+        let mut message = std::ptr::null_mut();
+        let r = unsafe {
+            llvm::target_machine::LLVMGetTargetFromTriple(
+                triple.as_ptr(),
+                &mut target,
+                &mut message,
+            ) == 1
+        };
+
+        let message = Message::from_raw(message);
+        if r || target.is_null() {
+            return Err(Error::Message(message));
+        }
+
+        Ok(Target(target))
+    }
+
     /// Get the default target
     pub fn default() -> Result<Target, Error> {
-        Target::new(default_target_triple())
+        Target::from_triple(default_target_triple())
     }
 
     /// Get host CPU name
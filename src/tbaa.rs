@@ -0,0 +1,96 @@
+use crate::*;
+
+/// `TbaaBuilder` creates type-based alias analysis metadata
+///
+/// Accesses tagged with type descriptors that don't share an ancestor below the root are assumed
+/// not to alias
+pub struct TbaaBuilder<'a> {
+    ctx: Context<'a>,
+    root: Metadata<'a>,
+}
+
+impl<'a> TbaaBuilder<'a> {
+    /// Create a new TBAA root with the given name, type descriptors from different roots are
+    /// always assumed to alias
+    pub fn new(ctx: &Context<'a>, name: impl AsRef<str>) -> Result<TbaaBuilder<'a>, Error> {
+        let root = Metadata::new_node(ctx, [Metadata::new_string(ctx, name)?])?;
+        Ok(TbaaBuilder {
+            ctx: ctx.clone(),
+            root,
+        })
+    }
+
+    /// Get the root node
+    pub fn root(&self) -> Metadata<'a> {
+        self.root
+    }
+
+    fn offset(&self, offset: u64) -> Result<Metadata<'a>, Error> {
+        Ok(Const::int(Type::i64(&self.ctx)?, offset as i64)?
+            .as_ref()
+            .to_metadata())
+    }
+
+    /// Create a scalar type descriptor, when `parent` is `None` the type is a child of the root
+    pub fn scalar_type(
+        &self,
+        name: impl AsRef<str>,
+        parent: Option<Metadata<'a>>,
+    ) -> Result<Metadata<'a>, Error> {
+        Metadata::new_node(
+            &self.ctx,
+            [
+                Metadata::new_string(&self.ctx, name)?,
+                parent.unwrap_or(self.root),
+                self.offset(0)?,
+            ],
+        )
+    }
+
+    /// Create a struct type descriptor from a list of (field type descriptor, byte offset) pairs
+    pub fn struct_type(
+        &self,
+        name: impl AsRef<str>,
+        fields: impl AsRef<[(Metadata<'a>, u64)]>,
+    ) -> Result<Metadata<'a>, Error> {
+        let mut md = vec![Metadata::new_string(&self.ctx, name)?];
+        for (t, offset) in fields.as_ref() {
+            md.push(*t);
+            md.push(self.offset(*offset)?);
+        }
+        Metadata::new_node(&self.ctx, md)
+    }
+
+    /// Create an access tag for a value of type `access` at `offset` bytes into `base`
+    pub fn access_tag(
+        &self,
+        base: Metadata<'a>,
+        access: Metadata<'a>,
+        offset: u64,
+    ) -> Result<Metadata<'a>, Error> {
+        Metadata::new_node(&self.ctx, [base, access, self.offset(offset)?])
+    }
+
+    /// Create an access tag for memory that is never modified
+    pub fn const_access_tag(
+        &self,
+        base: Metadata<'a>,
+        access: Metadata<'a>,
+        offset: u64,
+    ) -> Result<Metadata<'a>, Error> {
+        Metadata::new_node(
+            &self.ctx,
+            [base, access, self.offset(offset)?, self.offset(1)?],
+        )
+    }
+
+    /// Create an access tag for a scalar access that isn't part of a struct
+    pub fn scalar_tag(&self, t: Metadata<'a>) -> Result<Metadata<'a>, Error> {
+        self.access_tag(t, t, 0)
+    }
+
+    /// Attach an access tag to a load or store
+    pub fn attach(&self, instr: Instr<'a>, tag: Metadata<'a>) {
+        instr.set_metadata_kind(MetadataKind::Tbaa, tag)
+    }
+}
@@ -6,11 +6,11 @@ fn codegen() -> Result<(), Error> {
 
     let i32 = Type::of::<i32>(jit.context())?;
 
-    let ft = FuncType::new(i32, &[i32, i32])?;
+    let ft = FuncType::new(i32, [i32, i32])?;
     jit.declare_function("testing", ft, |builder, f| {
         let params = f.params();
         let a = builder.add(params[0], params[1], "a")?;
-        builder.ret(&a)
+        builder.ret(a)
     })?;
 
     println!("{}", jit.module());
@@ -22,7 +22,7 @@ fn codegen() -> Result<(), Error> {
     let x: i32 = testing(1i32, 2i32);
     assert_eq!(x, 3);
 
-    Codegen::new(jit.module(), &["testing"], true)?;
+    Codegen::new(jit.module(), ["testing"], true)?;
 
     Ok(())
 }
@@ -32,13 +32,13 @@ fn if_then_else() -> Result<(), Error> {
     let jit = Jit::new("test_if_then_else", None)?;
 
     let f32 = Type::float(jit.context())?;
-    let ft = FuncType::new(f32, &[f32])?;
+    let ft = FuncType::new(f32, [f32])?;
 
     jit.declare_function("testing", ft, |builder, f| {
         let params = f.params();
         let cond = builder.fcmp(
             Fcmp::LLVMRealULT,
-            &params[0],
+            params[0],
             Const::real(f32, 10.0)?,
             "cond",
         )?;
@@ -60,7 +60,283 @@ fn if_then_else() -> Result<(), Error> {
     assert_eq!(x, 2.0);
     assert_eq!(y, 1.0);
 
-    Codegen::new(jit.module(), &["testing"], false)?;
+    Codegen::new(jit.module(), ["testing"], false)?;
+
+    Ok(())
+}
+
+#[test]
+fn if_then() -> Result<(), Error> {
+    let jit = Jit::new("test_if_then", None)?;
+
+    let i32 = Type::of::<i32>(jit.context())?;
+    let ft = FuncType::new(i32, [i32])?;
+
+    jit.declare_function("abs", ft, |build, f| {
+        let x = f.param(0)?;
+        let cond = build.icmp(Icmp::LLVMIntSLT, x, Const::int(i32, 0)?, "cond")?;
+        build.if_then(cond, |build| {
+            build.ret(build.neg(x, "neg")?)?;
+            Ok(())
+        })?;
+        build.ret(x)
+    })?;
+
+    jit.declare_function("testing", ft, |build, f| {
+        let x = f.param(0)?;
+        let cond = build.icmp(Icmp::LLVMIntSGT, x, Const::int(i32, 10)?, "cond")?;
+        let ite = build.if_then_else_opt(
+            cond,
+            |build| {
+                build.ret(Const::int(i32, 0)?)?;
+                Ok(None)
+            },
+            |build| Ok(Some(build.mul(x, Const::int(i32, 2)?, "mul")?.into())),
+        )?;
+        let ite = ite.expect("if_then_else_opt should produce a value");
+        assert_eq!(ite.count_incoming(), 1);
+        build.ret(build.add(ite, Const::int(i32, 1)?, "add")?)
+    })?;
+
+    jit.declare_function("sign", ft, |build, f| {
+        let x = f.param(0)?;
+        let cond = build.icmp(Icmp::LLVMIntSLT, x, Const::int(i32, 0)?, "cond")?;
+        let mut ret = None;
+        let ite = build.if_then_else_opt(
+            cond,
+            |build| {
+                build.ret(Const::int_sext(i32, -1)?)?;
+                Ok(None)
+            },
+            |build| {
+                ret = Some(build.ret(Const::int(i32, 1)?)?);
+                Ok(None)
+            },
+        )?;
+        assert!(ite.is_none());
+        assert!(build.insertion_block().is_err());
+        ret.ok_or(Error::InvalidBasicBlock)
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let abs: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("abs")? };
+    assert_eq!(abs(-3), 3);
+    assert_eq!(abs(4), 4);
+
+    let testing: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("testing")? };
+    assert_eq!(testing(11), 0);
+    assert_eq!(testing(5), 11);
+
+    let sign: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("sign")? };
+    assert_eq!(sign(-4), -1);
+    assert_eq!(sign(4), 1);
+
+    Ok(())
+}
+
+#[test]
+fn ssa_variables() -> Result<(), Error> {
+    let jit = Jit::new("test_ssa_variables", None)?;
+
+    let ctx = jit.context();
+    let i64 = Type::of::<i64>(ctx)?;
+    let ft = FuncType::new(i64, [i64])?;
+    let mut first = None;
+
+    jit.declare_function("testing", ft, |build, f| {
+        let entry = build.insertion_block()?;
+        let header = BasicBlock::append(ctx, f, "header")?;
+        let body = BasicBlock::append(ctx, f, "body")?;
+        let exit = BasicBlock::append(ctx, f, "exit")?;
+
+        let i = build.declare_var(i64);
+        let acc = build.declare_var(i64);
+        let n = build.declare_var(i64);
+        first = Some(i);
+
+        build.def_var(i, Const::int(i64, 0)?)?;
+        build.def_var(acc, Const::int(i64, 0)?)?;
+        build.def_var(n, f.param(0)?)?;
+        build.br(header)?;
+        build.seal_block(entry)?;
+
+        build.position_at_end(header);
+        let cond = build.icmp(
+            Icmp::LLVMIntSLT,
+            build.use_var(i)?,
+            build.use_var(n)?,
+            "cond",
+        )?;
+        build.cond_br(cond, body, exit)?;
+
+        build.position_at_end(body);
+        let x = build.add(build.use_var(acc)?, build.use_var(i)?, "acc")?;
+        build.def_var(acc, x)?;
+        let x = build.add(build.use_var(i)?, Const::int(i64, 1)?, "i")?;
+        build.def_var(i, x)?;
+        build.br(header)?;
+        build.seal_block(body)?;
+        build.seal_block(header)?;
+
+        build.position_at_end(exit);
+        build.seal_block(exit)?;
+        let ret = build.ret(build.use_var(acc)?);
+
+        // `n` is never reassigned in the loop so only `i` and `acc` need a phi
+        let mut phis = 0;
+        let mut instr = Instr(header.first_instruction()?);
+        while instr.op_code() == OpCode::LLVMPHI {
+            phis += 1;
+            instr = instr.next_instruction()?;
+        }
+        assert_eq!(phis, 2);
+
+        ret
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let testing: extern "C" fn(i64) -> i64 = unsafe { jit.engine().function("testing")? };
+    assert_eq!(testing(0), 0);
+    assert_eq!(testing(5), 10);
+
+    // Variables are reset for each function body
+    jit.declare_function("testing2", ft, |build, f| {
+        let x = build.declare_var(i64);
+        assert_eq!(Some(x), first);
+        build.def_var(x, f.param(0)?)?;
+        build.seal_block(build.insertion_block()?)?;
+        build.ret(build.use_var(x)?)
+    })?;
+
+    // Definitions don't carry over between hand-built functions
+    let build = Builder::new(ctx)?;
+    let x = build.declare_var(i64);
+    let a = jit.define_function("hand_a", ft)?;
+    let a_entry = BasicBlock::append(ctx, a, "entry")?;
+    build.position_at_end(a_entry);
+    build.seal_block(a_entry)?;
+    build.def_var(x, a.param(0)?)?;
+    build.ret(build.use_var(x)?)?;
+
+    let b = jit.define_function("hand_b", ft)?;
+    let b_entry = BasicBlock::append(ctx, b, "entry")?;
+    build.position_at_end(b_entry);
+    build.seal_block(b_entry)?;
+    let v = build.use_var(x)?;
+    assert!(v.is_undef());
+    build.ret(v)?;
+    jit.module().verify()?;
+
+    Ok(())
+}
+
+#[test]
+fn match_value() -> Result<(), Error> {
+    let jit = Jit::new("test_match_value", None)?;
+
+    let i32 = Type::of::<i32>(jit.context())?;
+    let ft = FuncType::new(i32, [i32])?;
+
+    jit.declare_function("testing", ft, |build, f| {
+        let x = f.param(0)?;
+        let res = build.match_value(
+            x,
+            &mut [
+                (
+                    Const::int(i32, 0)?,
+                    &mut |_| Ok(Const::int(i32, 10)?.into()),
+                ),
+                (
+                    Const::int(i32, 1)?,
+                    &mut |_| Ok(Const::int(i32, 20)?.into()),
+                ),
+                (Const::int(i32, 5)?, &mut |build| {
+                    Ok(build.mul(x, Const::int(i32, 10)?, "mul")?.into())
+                }),
+            ],
+            |_| Const::int_sext(i32, -1),
+        )?;
+        build.ret(res.ok_or(Error::InvalidBasicBlock)?)
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let testing: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("testing")? };
+    assert_eq!(testing(0), 10);
+    assert_eq!(testing(1), 20);
+    assert_eq!(testing(5), 50);
+    assert_eq!(testing(3), -1);
+
+    Ok(())
+}
+
+#[test]
+fn match_value_terminator() -> Result<(), Error> {
+    let jit = Jit::new("test_match_value_terminator", None)?;
+
+    let i32 = Type::of::<i32>(jit.context())?;
+    let ft = FuncType::new(i32, [i32])?;
+
+    jit.declare_function("testing", ft, |build, f| {
+        let x = f.param(0)?;
+        let res = build
+            .match_value(
+                x,
+                &mut [
+                    (
+                        Const::int(i32, 0)?,
+                        &mut |_| Ok(Const::int(i32, 10)?.into()),
+                    ),
+                    (Const::int(i32, 1)?, &mut |build| {
+                        Ok(build.ret(Const::int(i32, 5)?)?.into())
+                    }),
+                ],
+                |_| Const::int_sext(i32, -1),
+            )?
+            .ok_or(Error::InvalidBasicBlock)?;
+        assert_eq!(res.count_incoming(), 2);
+        build.ret(res)
+    })?;
+
+    // Every branch returns so there is no merge block and no result
+    jit.declare_function("all_return", ft, |build, f| {
+        let x = f.param(0)?;
+        let mut cases = 0;
+        let mut ret = None;
+        let res = build.match_value(
+            x,
+            &mut [(Const::int(i32, 0)?, &mut |build| {
+                cases += 1;
+                Ok(build.ret(Const::int(i32, 7)?)?.into())
+            })],
+            |build| {
+                let r = build.ret(x)?;
+                ret = Some(r);
+                Ok(r)
+            },
+        )?;
+        assert!(res.is_none());
+        assert_eq!(cases, 1);
+        assert!(build.insertion_block().is_err());
+        ret.ok_or(Error::InvalidBasicBlock)
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let testing: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("testing")? };
+    assert_eq!(testing(0), 10);
+    assert_eq!(testing(1), 5);
+    assert_eq!(testing(3), -1);
+
+    let all_return: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("all_return")? };
+    assert_eq!(all_return(0), 7);
+    assert_eq!(all_return(3), 3);
 
     Ok(())
 }
@@ -71,7 +347,7 @@ fn for_loop() -> Result<(), Error> {
 
     let i64 = Type::int(jit.context(), 64)?;
 
-    let ft = FuncType::new(i64, &[i64])?;
+    let ft = FuncType::new(i64, [i64])?;
     jit.declare_function("testing", ft, |build, f| {
         let params = f.params();
         let one = Const::int_sext(i64, 1)?;
@@ -96,7 +372,1266 @@ fn for_loop() -> Result<(), Error> {
     assert_eq!(x, 99);
 
     let (_ctx, _builder, engine) = jit.into_inner();
-    Codegen::new(&engine.into_module()?, &["testing"], true)?;
+    Codegen::new(&engine.into_module()?, ["testing"], true)?;
+
+    Ok(())
+}
+
+#[test]
+fn checked_arithmetic() -> Result<(), Error> {
+    let jit = Jit::new("test_checked_arithmetic", None)?;
+
+    let i32 = Type::of::<i32>(jit.context())?;
+    let u8 = Type::of::<u8>(jit.context())?;
+
+    let ft = FuncType::new(i32, [i32, i32])?;
+    jit.declare_function("checked_add", ft, |build, f| {
+        let params = f.params();
+        let (sum, overflow) = build.checked_add(params[0], params[1], true, "sum")?;
+        let ret = build.select(overflow, Const::int_sext(i32, -1)?, sum, "ret")?;
+        build.ret(ret)
+    })?;
+
+    let ft = FuncType::new(u8, [u8, u8])?;
+    jit.declare_function("saturating_sub", ft, |build, f| {
+        let params = f.params();
+        let x = build.saturating_sub(params[0], params[1], false, "x")?;
+        build.ret(x)
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let checked_add: extern "C" fn(i32, i32) -> i32 =
+        unsafe { jit.engine().function("checked_add")? };
+    assert_eq!(checked_add(1, 2), 3);
+    assert_eq!(checked_add(i32::MAX, 1), -1);
+
+    let f = jit.module().function("checked_add")?;
+    assert!(f.as_ref().module().is_ok());
+    assert!(Const::int(i32, 1)?.as_ref().module().is_err());
+
+    let saturating_sub: extern "C" fn(u8, u8) -> u8 =
+        unsafe { jit.engine().function("saturating_sub")? };
+    assert_eq!(saturating_sub(10, 3), 7);
+    assert_eq!(saturating_sub(3, 10), 0);
+
+    Ok(())
+}
+
+#[test]
+fn while_loop() -> Result<(), Error> {
+    let jit = Jit::new("test_while_loop", None)?;
+
+    let i64 = Type::of::<i64>(jit.context())?;
+
+    let ft = FuncType::new(i64, [i64])?;
+    jit.declare_function("testing", ft, |build, f| {
+        let n = f.param(0)?;
+        let zero = Const::int(i64, 0)?;
+        let one = Const::int(i64, 1)?;
+        let res = build.while_loop_with(
+            [zero.into(), zero.into()],
+            |build, vars| build.icmp(Icmp::LLVMIntSLT, vars[0], n, "cond"),
+            |build, _, vars| {
+                let acc = build.add(vars[1], vars[0], "acc")?;
+                let i = build.add(vars[0], one, "i")?;
+                Ok([i.into(), acc.into()])
+            },
+        )?;
+        build.ret(res[1])
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let testing: extern "C" fn(i64) -> i64 = unsafe { jit.engine().function("testing")? };
+    assert_eq!(testing(0), 0);
+    assert_eq!(testing(5), 10);
+
+    Ok(())
+}
+
+#[test]
+fn loop_break_continue() -> Result<(), Error> {
+    let jit = Jit::new("test_loop_break_continue", None)?;
+
+    let ctx = jit.context();
+    let i64 = Type::of::<i64>(ctx)?;
+
+    // Sum of odd numbers less than n
+    let ft = FuncType::new(i64, [i64])?;
+    jit.declare_function("testing", ft, |build, f| {
+        let n = f.param(0)?;
+        let zero = Const::int(i64, 0)?;
+        let one = Const::int(i64, 1)?;
+        let res = build.loop_([zero.into(), zero.into()], |build, lp, vars| {
+            let done_bb = BasicBlock::append(ctx, f, "done")?;
+            let next_bb = BasicBlock::append(ctx, f, "next")?;
+            let odd_bb = BasicBlock::append(ctx, f, "odd")?;
+
+            let done = build.icmp(Icmp::LLVMIntEQ, vars[0], n, "done")?;
+            build.cond_br(done, done_bb, next_bb)?;
+
+            build.position_at_end(done_bb);
+            assert!(matches!(
+                lp.break_(build, [vars[0]]),
+                Err(Error::LoopValueCount(2, 1))
+            ));
+            lp.break_(build, vars)?;
+
+            build.position_at_end(next_bb);
+            let i = build.add(vars[0], one, "i")?;
+            let bit = build.and(vars[0], one, "bit")?;
+            let even = build.icmp(Icmp::LLVMIntEQ, bit, zero, "even")?;
+            let skip_bb = BasicBlock::append(ctx, f, "skip")?;
+            build.cond_br(even, skip_bb, odd_bb)?;
+
+            build.position_at_end(skip_bb);
+            lp.continue_(build, [i.into(), vars[1]])?;
+
+            build.position_at_end(odd_bb);
+            let acc = build.add(vars[1], vars[0], "acc")?;
+            Ok([i.into(), acc.into()])
+        })?;
+        build.ret(res.ok_or(Error::InvalidBasicBlock)?[1])
+    })?;
+
+    // Smallest i where i * i >= n, the loop is only left by returning
+    jit.declare_function("no_break", ft, |build, f| {
+        let n = f.param(0)?;
+        let zero = Const::int(i64, 0)?;
+        let one = Const::int(i64, 1)?;
+        let mut ret = None;
+        let res = build.loop_([zero.into()], |build, _lp, vars| {
+            let ret_bb = BasicBlock::append(ctx, f, "ret")?;
+            let next_bb = BasicBlock::append(ctx, f, "next")?;
+            let sq = build.mul(vars[0], vars[0], "sq")?;
+            let done = build.icmp(Icmp::LLVMIntSGE, sq, n, "done")?;
+            build.cond_br(done, ret_bb, next_bb)?;
+
+            build.position_at_end(ret_bb);
+            ret = Some(build.ret(vars[0])?);
+
+            build.position_at_end(next_bb);
+            Ok([build.add(vars[0], one, "i")?.into()])
+        })?;
+        assert!(res.is_none());
+        assert!(build.insertion_block().is_err());
+        ret.ok_or(Error::InvalidBasicBlock)
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let testing: extern "C" fn(i64) -> i64 = unsafe { jit.engine().function("testing")? };
+    assert_eq!(testing(0), 0);
+    assert_eq!(testing(5), 4);
+    assert_eq!(testing(10), 25);
+
+    let no_break: extern "C" fn(i64) -> i64 = unsafe { jit.engine().function("no_break")? };
+    assert_eq!(no_break(10), 4);
+
+    Ok(())
+}
+
+#[test]
+fn llvm_type_of() -> Result<(), Error> {
+    let ctx = Context::new()?;
+
+    assert_eq!(Type::of::<bool>(&ctx)?.to_string(), "i1");
+    assert_eq!(
+        Type::of::<usize>(&ctx)?.int_width(),
+        std::mem::size_of::<usize>() * 8
+    );
+    assert_eq!(Type::of::<[u8; 3]>(&ctx)?.to_string(), "[3 x i8]");
+    assert_eq!(Type::of::<(i32, f64)>(&ctx)?.to_string(), "{ i32, double }");
+
+    let t = Type::of::<extern "C" fn(*const [f32; 4], usize) -> bool>(&ctx)?;
+    let usize_t = Type::of::<usize>(&ctx)?;
+    assert_eq!(t.to_string(), format!("i1 ([4 x float]*, {})*", usize_t));
+
+    let t = Type::of::<unsafe extern "C" fn()>(&ctx)?;
+    assert_eq!(t.to_string(), "void ()*");
+
+    let data = TargetData::new("e-p:32:32")?;
+    assert_eq!(Type::of_target::<usize>(&ctx, &data)?.to_string(), "i32");
+    let t = Type::of_target::<extern "C" fn(*const [isize; 2], usize) -> bool>(&ctx, &data)?;
+    assert_eq!(t.to_string(), "i1 ([2 x i32]*, i32)*");
+
+    #[cfg(target_arch = "x86_64")]
+    assert_eq!(
+        Type::of::<std::arch::x86_64::__m128>(&ctx)?.to_string(),
+        "<4 x float>"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn identity() -> Result<(), Error> {
+    let jit = Jit::new("test_identity", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::of::<i32>(ctx)?;
+    assert_eq!(i32, Type::i32(ctx)?);
+    assert_ne!(i32, Type::i64(ctx)?);
+
+    let ft = FuncType::new(i32, [i32, i32])?;
+    let f = jit.declare_function("testing", ft, |build, f| {
+        let params = f.params();
+        let a = build.add(params[0], params[1], "a")?;
+
+        let mut facts = std::collections::HashMap::new();
+        facts.insert(params[0], "x");
+        facts.insert(params[1], "y");
+        facts.insert(a.into(), "sum");
+        assert_eq!(facts[&f.param(1)?], "y");
+        assert_eq!(facts[&Value::from(a)], "sum");
+
+        assert_eq!(format!("{:?}", Value::from(a)), "  %a = add i32 %0, %1");
+        build.ret(a)
+    })?;
+
+    assert_eq!(f, jit.module().function("testing")?);
+    let entry = f.entry_basic_block()?;
+    assert_eq!(entry, f.first_basic_block()?);
+    assert!(entry.to_string().trim_start().starts_with("entry:"));
+
+    Ok(())
+}
+
+#[test]
+fn type_desc() -> Result<(), Error> {
+    let ctx = Context::new()?;
+
+    let i32 = Type::i32(&ctx)?;
+    assert_eq!(i32.describe()?, TypeDesc::Int(32));
+
+    let v = Type::float(&ctx)?.vector(4)?;
+    assert_eq!(v.vector_len(), 4);
+    assert_eq!(
+        v.describe()?,
+        TypeDesc::Vector(Box::new(TypeDesc::Float(FloatKind::Float)), 4)
+    );
+
+    let p = i32.pointer(Some(1))?;
+    assert_eq!(p.pointer_address_space(), 1);
+
+    let mut node = StructType::new_with_name(&ctx, "node")?;
+    let node_ptr = node.as_ref().pointer(None)?;
+    node.set_body([i32, node_ptr]);
+
+    let ft = FuncType::new_var_arg(node_ptr, [p, Type::i8(&ctx)?.array(16)?])?;
+    let desc = Type::from(ft).describe()?;
+    println!("{:?}", desc);
+
+    let node_desc = TypeDesc::Struct {
+        name: Some("node".into()),
+        fields: Some(vec![
+            TypeDesc::Int(32),
+            TypeDesc::Pointer {
+                element: Box::new(TypeDesc::Struct {
+                    name: Some("node".into()),
+                    fields: None,
+                    packed: false,
+                }),
+                address_space: 0,
+            },
+        ]),
+        packed: false,
+    };
+    match &desc {
+        TypeDesc::Function {
+            return_type,
+            params,
+            var_arg: true,
+        } => {
+            assert_eq!(
+                **return_type,
+                TypeDesc::Pointer {
+                    element: Box::new(node_desc.clone()),
+                    address_space: 0
+                }
+            );
+            assert_eq!(params[1], TypeDesc::Array(Box::new(TypeDesc::Int(8)), 16));
+        }
+        _ => panic!("expected function type"),
+    }
+
+    assert_eq!(Type::from_desc(&ctx, &desc)?, ft.into());
+
+    let other = Context::new()?;
+    let t = Type::from_desc(&other, &node_desc)?;
+    assert_eq!(t.to_string(), "%node = type { i32, %node* }");
+    assert_eq!(t.describe()?, node_desc);
+
+    let other_node = TypeDesc::Struct {
+        name: Some("node".into()),
+        fields: Some(vec![TypeDesc::Int(64)]),
+        packed: false,
+    };
+    assert!(matches!(
+        Type::from_desc(&other, &other_node),
+        Err(Error::StructMismatch(_))
+    ));
+
+    let _ = StructType::new_with_name(&other, "opaque")?;
+    let opaque = TypeDesc::Struct {
+        name: Some("opaque".into()),
+        fields: Some(vec![TypeDesc::Int(8)]),
+        packed: true,
+    };
+    let t = Type::from_desc(&other, &opaque)?;
+    assert_eq!(t.to_string(), "%opaque = type <{ i8 }>");
+
+    Ok(())
+}
+
+#[test]
+fn data_array() -> Result<(), Error> {
+    let jit = Jit::new("test_data_array", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::of::<i32>(ctx)?;
+    let i64 = Type::of::<i64>(ctx)?;
+
+    let squares: Vec<i32> = (0..1000).map(|x| x * x).collect();
+    let table = Const::data_array(ctx, &squares)?;
+    assert_eq!(table.as_ref().type_of()?.to_string(), "[1000 x i32]");
+    let global = jit.module().declare_global("squares", table)?;
+
+    let bytes = Const::bytes(ctx, b"a\0b")?;
+    assert_eq!(bytes.as_ref().to_string(), "[3 x i8] c\"a\\00b\"");
+
+    let floats = Const::data_array(ctx, [0.5f64, 1.5])?;
+    assert_eq!(
+        floats.as_ref().to_string(),
+        "[2 x double] [double 5.000000e-01, double 1.500000e+00]"
+    );
+
+    let is_data_array =
+        |c: Const| unsafe { !llvm::core::LLVMIsAConstantDataArray(c.as_ref().llvm()).is_null() };
+    assert!(is_data_array(table));
+    assert!(is_data_array(floats));
+    assert!(is_data_array(Const::data_array(ctx, [1u16, 2, 3])?));
+    assert!(is_data_array(Const::data_array(ctx, [-1i64, 2])?));
+    let t = Const::data_array(ctx, [-1i16, 258])?;
+    assert_eq!(t.as_ref().to_string(), "[2 x i16] [i16 -1, i16 258]");
+
+    let ft = FuncType::new(i32, [i64])?;
+    jit.declare_function("square", ft, |build, f| {
+        let zero = Const::int(i64, 0)?;
+        let ptr =
+            build.in_bounds_gep(i32.array(1000)?, global, [zero.into(), f.param(0)?], "ptr")?;
+        let x = build.load(i32, ptr, "x")?;
+        build.ret(x)
+    })?;
+
+    jit.module().verify()?;
+
+    let square: extern "C" fn(i64) -> i32 = unsafe { jit.engine().function("square")? };
+    assert_eq!(square(3), 9);
+    assert_eq!(square(999), 998001);
+
+    Ok(())
+}
+
+#[test]
+fn const_to_rust() -> Result<(), Error> {
+    let ctx = Context::new()?;
+
+    let i32 = Type::i32(&ctx)?;
+    let f64 = Type::double(&ctx)?;
+    let ptr = i32.pointer(None)?;
+
+    let table = Const::data_array(&ctx, [1i32, -2, 3])?;
+    assert_eq!(table.to_rust::<Vec<i32>>()?, vec![1, -2, 3]);
+    assert_eq!(table.to_rust::<[i32; 3]>()?, [1, -2, 3]);
+
+    let s = Const::string(&ctx, "hello")?;
+    assert_eq!(s.to_rust::<String>()?, "hello");
+
+    let x = Const::crate_struct(
+        &ctx,
+        [
+            Const::int(i32, 7)?.into(),
+            Const::real(f64, 0.25)?.into(),
+            Const::pointer_null(ptr)?.into(),
+            Const::null(i32.array(2)?)?.into(),
+        ],
+    )?;
+    assert_eq!(
+        x.to_value()?,
+        ConstValue::Struct(vec![
+            ConstValue::Int { bits: 32, value: 7 },
+            ConstValue::Float(0.25),
+            ConstValue::Null,
+            ConstValue::Array(vec![
+                ConstValue::Int { bits: 32, value: 0 },
+                ConstValue::Int { bits: 32, value: 0 }
+            ]),
+        ])
+    );
+    let (a, b, c, d) = x.to_rust::<(u32, f64, Option<u64>, [i32; 2])>()?;
+    assert_eq!((a, b, c, d), (7, 0.25, None, [0, 0]));
+
+    let v = Const::create_vector([Const::real(f64, 1.0)?.into(), Const::undef(f64)?.into()])?;
+    assert_eq!(
+        v.to_value()?,
+        ConstValue::Vector(vec![ConstValue::Float(1.0), ConstValue::Undef])
+    );
+
+    assert!(table.to_rust::<Vec<f64>>().is_err());
+
+    assert_eq!(Const::int(i32, -2)?.to_rust::<i64>()?, -2);
+    assert_eq!(Const::int(i32, -2)?.to_rust::<u32>()?, 4294967294);
+    assert!(Const::int(i32, 300)?.to_rust::<u8>().is_err());
+    assert!(Const::int(i32, -200)?.to_rust::<i8>().is_err());
+    assert!(Const::int(i32, 2)?.to_rust::<bool>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn wide_int() -> Result<(), Error> {
+    let ctx = Context::new()?;
+
+    let i256 = Type::int(&ctx, 256)?;
+    let x = Const::int_wide(i256, [1, 2, 3, 4])?;
+    assert_eq!(x.get_words(), Some(vec![1, 2, 3, 4]));
+    assert_eq!(x.get_unsigned_int(), None);
+    assert_eq!(x.get_u128(), None);
+    assert_eq!(
+        x.to_value()?,
+        ConstValue::WideInt {
+            bits: 256,
+            words: vec![1, 2, 3, 4]
+        }
+    );
+    assert!(x.to_rust::<u128>().is_err());
+    assert_eq!(Const::int_wide(i256, [5, 0, 0, 0])?.to_rust::<u128>()?, 5);
+    assert_eq!(Const::int_wide(i256, [u64::MAX; 4])?.to_rust::<i128>()?, -1);
+
+    let p = Const::int_from_str(
+        i256,
+        "115792089237316195423570985008687907853269984665640564039457584007908834671663",
+        10,
+    )?;
+    assert_eq!(
+        p.get_words(),
+        Some(vec![
+            0xfffffffefffffc2f,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0xffffffffffffffff
+        ])
+    );
+    assert!(Const::int_from_str(i256, "12z", 10).is_err());
+
+    let i8 = Type::i8(&ctx)?;
+    assert!(Const::int_from_str(i8, "100000000000000000000000", 10).is_err());
+    assert!(Const::int_from_str(i8, "256", 10).is_err());
+    assert!(Const::int_from_str(i8, "-129", 10).is_err());
+    assert_eq!(
+        Const::int_from_str(i8, "255", 10)?.get_unsigned_int(),
+        Some(255)
+    );
+    assert_eq!(
+        Const::int_from_str(i8, "-128", 10)?.get_signed_int(),
+        Some(-128)
+    );
+    assert_eq!(
+        Const::int_from_str(i8, "-0000000000000080", 16)?.get_signed_int(),
+        Some(-128)
+    );
+
+    let i128 = Type::of::<i128>(&ctx)?;
+    let y = Const::int_from_str(i128, "-ff", 16)?;
+    assert_eq!(y.get_i128(), Some(-255));
+    assert_eq!(y.get_u128(), Some(u128::MAX - 254));
+    assert_eq!(y.to_rust::<u128>()?, u128::MAX - 254);
+    assert_eq!(y.to_rust::<i128>()?, -255);
+    assert_eq!(Const::int(Type::i64(&ctx)?, -1)?.to_rust::<i128>()?, -1);
+    assert_eq!(
+        Const::int(Type::i64(&ctx)?, -1)?.to_rust::<u128>()?,
+        u64::MAX as u128
+    );
+
+    let table = Const::data_array(&ctx, [u128::MAX, 1u128 << 100])?;
+    assert_eq!(table.to_rust::<Vec<u128>>()?, vec![u128::MAX, 1 << 100]);
+
+    Ok(())
+}
+
+#[test]
+fn const_evaluate() -> Result<(), Error> {
+    let ctx = Context::new()?;
+    let module = Module::new(&ctx, "test_const_evaluate")?;
+    let data = TargetData::new("e-m:e-p:64:64-i64:64-n8:16:32:64-S128")?;
+
+    let i16 = Type::i16(&ctx)?;
+    let i32 = Type::i32(&ctx)?;
+    let i64 = Type::i64(&ctx)?;
+
+    let table = Const::data_array(&ctx, [1i32, 2, 3, 4])?;
+    assert_eq!(
+        table.evaluate(&data)?,
+        [1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]
+    );
+    let big_endian = TargetData::new("E-p:64:64-i64:64")?;
+    assert_eq!(
+        Const::int(i32, 0x01020304)?.evaluate(&big_endian)?,
+        [1, 2, 3, 4]
+    );
+
+    let table = module.declare_global("table", table)?.to_const()?;
+    let ptr = table.gep(
+        i32.array(4)?,
+        [Const::int(i64, 0)?.into(), Const::int(i64, 2)?.into()],
+    )?;
+
+    let x = Const::crate_struct(
+        &ctx,
+        [
+            Const::int(i16, -2)?.into(),
+            ptr.into(),
+            Const::real(Type::float(&ctx)?, 1.0)?.into(),
+            Const::pointer_null(i32.pointer(None)?)?.into(),
+            table.ptr_to_int(i64)?.into(),
+        ],
+    )?;
+    assert!(x.evaluate(&data).is_err());
+
+    let (bytes, relocations) = x.evaluate_with_relocations(&data)?;
+    assert_eq!(bytes.len(), 40);
+    assert_eq!(&bytes[0..2], &[0xfe, 0xff]);
+    assert_eq!(&bytes[16..20], &1.0f32.to_le_bytes());
+    assert!(bytes[8..16].iter().chain(&bytes[24..40]).all(|x| *x == 0));
+    assert_eq!(
+        relocations,
+        vec![
+            Relocation {
+                offset: 8,
+                size: 8,
+                symbol: "table".into(),
+                addend: 8
+            },
+            Relocation {
+                offset: 32,
+                size: 8,
+                symbol: "table".into(),
+                addend: 0
+            }
+        ]
+    );
+
+    let (_, relocations) = table.ptr_to_int(i32)?.evaluate_with_relocations(&data)?;
+    assert_eq!(relocations[0].size, 4);
+
+    let far = table.gep(
+        i32.array(4)?,
+        [
+            Const::int(i64, i64::MAX)?.into(),
+            Const::int(i64, 0)?.into(),
+        ],
+    )?;
+    assert!(far.evaluate_with_relocations(&data).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn block_address() -> Result<(), Error> {
+    let jit = Jit::new("test_block_address", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::i32(ctx)?;
+
+    let ft = FuncType::new(i32, [i32])?;
+    jit.declare_function("dispatch", ft, |build, f| {
+        let one = BasicBlock::append(ctx, f, "one")?;
+        let two = BasicBlock::append(ctx, f, "two")?;
+
+        let is_zero = build.icmp(Icmp::LLVMIntEQ, f.param(0)?, Const::int(i32, 0)?, "is_zero")?;
+        let addr = build.select(
+            is_zero,
+            Const::block_address(f, one)?,
+            Const::block_address(f, two)?,
+            "addr",
+        )?;
+        let mut br = build.indirect_br(addr, 2)?;
+        br.add_dest(one);
+        br.add_dest(two);
+
+        build.position_at_end(one);
+        build.ret(Const::int(i32, 1)?)?;
+
+        build.position_at_end(two);
+        build.ret(Const::int(i32, 2)?)
+    })?;
+
+    println!("{}", jit.module());
+    jit.module().verify()?;
+
+    let dispatch: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("dispatch")? };
+    assert_eq!(dispatch(0), 1);
+    assert_eq!(dispatch(5), 2);
+
+    Ok(())
+}
+
+#[test]
+fn alias_ifunc() -> Result<(), Error> {
+    let ctx = Context::new()?;
+    let module = Module::new(&ctx, "test_alias_ifunc")?;
+    let build = Builder::new(&ctx)?;
+
+    let i32 = Type::i32(&ctx)?;
+    let ft = FuncType::new(i32, [])?;
+    let f = module.declare_function(&build, "answer_v1", ft, |f| {
+        build.ret(Const::int(i32, 42)?)?;
+        Ok(f)
+    })?;
+
+    let alias = module.add_alias("answer", ft, f)?;
+    assert_eq!(module.alias("answer")?, alias);
+    assert_eq!(alias.aliasee()?, f.into());
+    assert_eq!(alias.value_type()?, ft.into());
+
+    let alias2 = module.add_alias("answer2", ft, f)?;
+    assert_eq!(module.first_alias()?, alias);
+    assert_eq!(alias.next_alias()?, alias2);
+    assert!(alias2.next_alias().is_err());
+    assert!(matches!(
+        module.add_alias("bad", i32, Const::int(i32, 1)?),
+        Err(Error::InvalidType)
+    ));
+
+    let ptr = Type::from(ft).pointer(None)?;
+    let resolver = module.declare_function(&build, "resolve", FuncType::new(ptr, [])?, |r| {
+        build.ret(f)?;
+        Ok(r)
+    })?;
+    let ifunc = module.add_ifunc("answer_dyn", ft, resolver)?;
+    assert_eq!(module.ifunc("answer_dyn")?, ifunc);
+    assert_eq!(module.first_ifunc()?, ifunc);
+    assert_eq!(ifunc.resolver()?, resolver);
+    assert_eq!(ifunc.func_type()?, ft);
+
+    println!("{}", module);
+    module.verify()?;
+
+    Ok(())
+}
+
+#[test]
+fn section_comdat() -> Result<(), Error> {
+    let ctx = Context::new()?;
+    let module = Module::new(&ctx, "test_section_comdat")?;
+
+    let mut table = module.declare_global("table", Const::data_array(&ctx, [1u32, 2, 3])?)?;
+    assert_eq!(table.section(), None);
+    table.set_section(".rodata.tables");
+    assert_eq!(table.section(), Some(".rodata.tables"));
+
+    let mut comdat = module.get_or_insert_comdat("table")?;
+    comdat.set_selection_kind(ComdatSelectionKind::LLVMLargestComdatSelectionKind);
+    table.set_comdat(comdat);
+    assert_eq!(table.comdat()?, module.get_or_insert_comdat("table")?);
+    assert_eq!(
+        table.comdat()?.selection_kind(),
+        ComdatSelectionKind::LLVMLargestComdatSelectionKind
+    );
+
+    table.set_unnamed_addr(UnnamedAddr::LLVMGlobalUnnamedAddr);
+    assert_eq!(table.unnamed_addr(), UnnamedAddr::LLVMGlobalUnnamedAddr);
+
+    table.set_dll_storage_class(DLLStorageClass::LLVMDLLExportStorageClass);
+    assert_eq!(
+        table.dll_storage_class(),
+        DLLStorageClass::LLVMDLLExportStorageClass
+    );
+
+    let ir = module.to_string();
+    println!("{}", ir);
+    assert!(ir.contains("$table = comdat largest"));
+    assert!(ir.contains(
+        "@table = dllexport unnamed_addr global [3 x i32] [i32 1, i32 2, i32 3], section \".rodata.tables\", comdat"
+    ));
+    module.verify()?;
+
+    Ok(())
+}
+
+#[test]
+fn debug_info() -> Result<(), Error> {
+    let jit = Jit::new("test_debug_info", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::i32(ctx)?;
+
+    let dib = DebugInfoBuilder::new(jit.module())?;
+    let file = dib.file("test.lang", "/tmp")?;
+    let cu = dib.compile_unit(
+        DWARFSourceLanguage::LLVMDWARFSourceLanguageC,
+        file,
+        "llama",
+        false,
+    )?;
+    let int = dib.basic_type("int", 32, DW_ATE_SIGNED)?;
+    let int_ptr = dib.pointer_type(int, 64, "int*")?;
+    let x = dib.member_type(cu, "x", file, 1, 32, 32, 0, int)?;
+    let next = dib.member_type(cu, "next", file, 2, 64, 64, 64, int_ptr)?;
+    dib.struct_type(cu, "node", file, 1, 128, 64, [x, next])?;
+    let ty = dib.subroutine_type(file, Some(int), [int, int])?;
+
+    let ft = FuncType::new(i32, [i32, i32])?;
+    let mut f = jit.define_function("debug_add", ft)?;
+    let sp = dib.function(cu, "debug_add", file, 3, ty, 0)?;
+    f.set_subprogram(sp);
+    assert_eq!(f.subprogram()?, sp);
+
+    let build = jit.build();
+    build.function_body(f, |build, entry| {
+        let loc = dib.location(3, 1, sp)?;
+        build.set_current_debug_location(Some(loc));
+        assert_eq!(build.current_debug_location()?, loc);
+
+        let a = dib.parameter_variable(sp, "a", 1, file, 3, int)?;
+        let expr = dib.expression([])?;
+        dib.insert_dbg_value_at_end(f.param(0)?, a, expr, loc, entry)?;
+
+        let block = dib.lexical_block(sp, file, 4, 1)?;
+        let loc = dib.location(4, 5, block)?;
+        build.set_current_debug_location(Some(loc));
+        let sum = build.alloca(i32, "sum")?;
+        let var = dib.auto_variable(block, "sum", file, 4, int)?;
+        dib.insert_declare_at_end(sum, var, expr, loc, entry)?;
+        let value = build.add(f.param(0)?, f.param(1)?, "value")?;
+        build.store(value, sum)?;
+        let ret = build.load(i32, sum, "ret")?;
+        build.set_current_debug_location(None);
+        build.ret(ret)
+    })?;
+    dib.finalize();
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("call void @llvm.dbg.declare(metadata i32* %sum"));
+    assert!(ir.contains("!DISubprogram(name: \"debug_add\""));
+    assert!(ir.contains("!DILocalVariable(name: \"sum\""));
+    assert!(ir.contains("!\"Debug Info Version\""));
+    jit.module().verify()?;
+    drop(dib);
+
+    let debug_add: extern "C" fn(i32, i32) -> i32 = unsafe { jit.engine().function("debug_add")? };
+    assert_eq!(debug_add(2, 3), 5);
+
+    Ok(())
+}
+
+#[test]
+fn named_metadata() -> Result<(), Error> {
+    let ctx = Context::new()?;
+    let module = Module::new(&ctx, "test_named_metadata")?;
+
+    assert!(module.named_metadata("llvm.ident")?.is_empty());
+    let ident = Metadata::new_node(&ctx, [Metadata::new_string(&ctx, "llama 0.14")?])?;
+    module.add_named_metadata_operand("llvm.ident", ident);
+    let features = Metadata::new_node(&ctx, [Metadata::new_string(&ctx, "simd")?])?;
+    module.add_named_metadata_operand("my.features", features);
+    module.add_named_metadata_operand("my.features", ident);
+
+    let md = module.named_metadata("my.features")?;
+    assert_eq!(md, [features, ident]);
+    assert_eq!(md[0].node()[0].as_str()?, "simd");
+
+    let version = Const::int(Type::i32(&ctx)?, 3)?;
+    module.add_module_flag(
+        ModuleFlagBehavior::LLVMModuleFlagBehaviorError,
+        "lang.version",
+        version.as_ref().to_metadata(),
+    );
+    module.add_module_flag(
+        ModuleFlagBehavior::LLVMModuleFlagBehaviorAppendUnique,
+        "lang.features",
+        features,
+    );
+
+    assert_eq!(module.module_flag("lang.features")?, features);
+    let flags = module.module_flags()?;
+    assert_eq!(flags.len(), 2);
+    assert_eq!(flags[0].0, ModuleFlagBehavior::LLVMModuleFlagBehaviorError);
+    assert_eq!(flags[0].1, "lang.version");
+    assert_eq!(flags[1].2, features);
+    assert!(module.module_flag("missing").is_err());
+
+    println!("{}", module);
+    module.verify()?;
+
+    Ok(())
+}
+
+#[test]
+fn instr_metadata() -> Result<(), Error> {
+    let jit = Jit::new("test_instr_metadata", None)?;
+
+    let ctx = jit.context();
+    for kind in [
+        MetadataKind::Dbg,
+        MetadataKind::Range,
+        MetadataKind::NonNull,
+        MetadataKind::AliasScope,
+        MetadataKind::Align,
+        MetadataKind::Loop,
+        MetadataKind::AccessGroup,
+    ] {
+        assert_eq!(ctx.md_kind_id(kind.name()), kind as u32);
+    }
+
+    let i32 = Type::i32(ctx)?;
+    let ptr = i32.pointer(None)?;
+    let ft = FuncType::new(i32, [ptr.pointer(None)?])?;
+    jit.declare_function("clamp_load", ft, |build, f| {
+        let p = build.load(ptr, f.param(0)?, "p")?;
+        p.set_nonnull()?;
+        let x = build.load(i32, p, "x")?;
+        x.set_range(0, 100)?;
+        assert!(matches!(p.set_range(0, 1), Err(Error::InvalidType)));
+        x.set_invariant_load()?;
+
+        let all = x.all_metadata()?;
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0, MetadataKind::Range as u32);
+        assert_eq!(x.get_metadata_kind(MetadataKind::Range)?, all[0].1);
+
+        let big = BasicBlock::append(ctx, f, "big")?;
+        let small = BasicBlock::append(ctx, f, "small")?;
+        let cond = build.icmp(Icmp::LLVMIntSGT, x, Const::int(i32, 50)?, "cond")?;
+        let br = build.cond_br(cond, big, small)?;
+        br.set_branch_weights([1, 99])?;
+
+        build.position_at_end(big);
+        build.ret(Const::int(i32, 50)?)?;
+        build.position_at_end(small);
+        build.ret(x)
+    })?;
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("!{!\"branch_weights\", i32 1, i32 99}"));
+    assert!(ir.contains("!{i32 0, i32 100}"));
+    assert!(ir.contains("!nonnull !"));
+    jit.module().verify()?;
+
+    let clamp_load: extern "C" fn(*const *const i32) -> i32 =
+        unsafe { jit.engine().function("clamp_load")? };
+    let x = 75;
+    assert_eq!(clamp_load(&(&x as *const i32)), 50);
+
+    Ok(())
+}
+
+#[test]
+fn tbaa() -> Result<(), Error> {
+    let jit = Jit::new("test_tbaa", None)?;
+
+    let ctx = jit.context();
+    let tbaa = TbaaBuilder::new(ctx, "test tbaa")?;
+    let char_t = tbaa.scalar_type("omnipotent char", None)?;
+    let int_t = tbaa.scalar_type("int", Some(char_t))?;
+    let float_t = tbaa.scalar_type("float", Some(char_t))?;
+    let pair_t = tbaa.struct_type("pair", [(int_t, 0), (float_t, 4)])?;
+    assert_eq!(int_t.node()[1], char_t);
+    assert_eq!(pair_t.node().len(), 5);
+
+    let i32 = Type::i32(ctx)?;
+    let f32 = Type::float(ctx)?;
+    let ft = FuncType::new(i32, [i32.pointer(None)?, f32.pointer(None)?])?;
+    jit.declare_function("store_load", ft, |build, f| {
+        let a = build.store(Const::int(i32, 1)?, f.param(0)?)?;
+        tbaa.attach(a, tbaa.access_tag(pair_t, int_t, 0)?);
+        let b = build.store(Const::real(f32, 2.0)?, f.param(1)?)?;
+        tbaa.attach(b, tbaa.access_tag(pair_t, float_t, 4)?);
+        let x = build.load(i32, f.param(0)?, "x")?;
+        tbaa.attach(x, tbaa.scalar_tag(int_t)?);
+        assert_eq!(x.get_metadata_kind(MetadataKind::Tbaa)?.node()[0], int_t);
+        build.ret(x)
+    })?;
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("!tbaa !"));
+    assert!(ir.contains("!{!\"test tbaa\"}"));
+    jit.module().verify()?;
+
+    let store_load: extern "C" fn(*mut i32, *mut f32) -> i32 =
+        unsafe { jit.engine().function("store_load")? };
+    let mut a = 0;
+    let mut b = 0.0;
+    assert_eq!(store_load(&mut a, &mut b), 1);
+    assert_eq!(b, 2.0);
+
+    Ok(())
+}
+
+#[test]
+fn loop_hints() -> Result<(), Error> {
+    let jit = Jit::new("test_loop_hints", None)?;
+
+    let i64 = Type::int(jit.context(), 64)?;
+    let hints = LoopHints::new()
+        .must_progress()
+        .unroll_count(4)
+        .vectorize(true)
+        .vectorize_width(8)
+        .interleave_count(2);
+
+    let ft = FuncType::new(i64, [i64])?;
+    jit.declare_function("count", ft, |build, f| {
+        let n = f.param(0)?;
+        let one = Const::int_sext(i64, 1)?;
+        let x = build.for_loop_with_hints(
+            Const::int_sext(i64, 0)?,
+            &hints,
+            |x| build.icmp(Icmp::LLVMIntSLT, x, n, "cond"),
+            |x| build.add(x, one, "add"),
+            |x| Ok(*x),
+        )?;
+        build.ret(x)
+    })?;
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("!llvm.loop !"));
+    assert!(ir.contains("!{!\"llvm.loop.unroll.count\", i32 4}"));
+    assert!(ir.contains("!{!\"llvm.loop.vectorize.enable\", i1 true}"));
+    assert!(ir.contains("!{!\"llvm.loop.mustprogress\"}"));
+    jit.module().verify()?;
+
+    let md = hints.to_metadata(jit.context())?;
+    assert_eq!(md.node()[0], md);
+    assert_eq!(md.node().len(), 6);
+
+    let count: extern "C" fn(i64) -> i64 = unsafe { jit.engine().function("count")? };
+    assert_eq!(count(10), 9);
+
+    Ok(())
+}
+
+#[test]
+fn alias_scope() -> Result<(), Error> {
+    let jit = Jit::new("test_alias_scope", None)?;
+
+    let ctx = jit.context();
+    let domain = Metadata::alias_domain(ctx, "buffers")?;
+    let a_scope = Metadata::alias_scope(ctx, domain, "a")?;
+    let b_scope = Metadata::alias_scope(ctx, domain, "b")?;
+    assert_ne!(a_scope, b_scope);
+    assert_eq!(a_scope.node()[0], a_scope);
+    assert_eq!(a_scope.node()[1], domain);
+
+    let i32 = Type::i32(ctx)?;
+    let ptr = i32.pointer(None)?;
+    let ft = FuncType::new(i32, [ptr, ptr])?;
+    jit.declare_function("copy_load", ft, |build, f| {
+        let a = f.param(0)?;
+        let b = f.param(1)?;
+        // The store only writes `a` and the load only reads `b`, the memcpy touches both
+        let st = build.store(Const::int(i32, 7)?, a)?;
+        st.set_alias_scopes([a_scope])?;
+        st.set_noalias_scopes([b_scope])?;
+        let four = Const::int(Type::i64(ctx)?, 4)?;
+        let cp = build.memcpy(b, 4, a, 4, four)?;
+        cp.set_alias_scopes([a_scope, b_scope])?;
+        let x = build.load(i32, b, "x")?;
+        x.set_alias_scopes([b_scope])?;
+        x.set_noalias_scopes([a_scope])?;
+
+        fn scopes(i: Instr<'_>, kind: MetadataKind) -> Result<Vec<Metadata<'_>>, Error> {
+            Ok(i.get_metadata_kind(kind)?.node())
+        }
+        assert_eq!(scopes(st, MetadataKind::AliasScope)?, [a_scope]);
+        assert_eq!(scopes(st, MetadataKind::NoAlias)?, [b_scope]);
+        assert_eq!(scopes(cp, MetadataKind::AliasScope)?, [a_scope, b_scope]);
+        assert!(cp.get_metadata_kind(MetadataKind::NoAlias).is_err());
+        assert_eq!(scopes(x, MetadataKind::AliasScope)?, [b_scope]);
+        assert_eq!(scopes(x, MetadataKind::NoAlias)?, [a_scope]);
+        build.ret(x)
+    })?;
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("!alias.scope !"));
+    assert!(ir.contains("!noalias !"));
+    assert!(ir.contains("!\"buffers\"}"));
+    jit.module().verify()?;
+
+    let copy_load: extern "C" fn(*mut i32, *mut i32) -> i32 =
+        unsafe { jit.engine().function("copy_load")? };
+    let mut a = 0;
+    let mut b = 0;
+    assert_eq!(copy_load(&mut a, &mut b), 7);
+
+    Ok(())
+}
+
+#[test]
+fn attributes() -> Result<(), Error> {
+    let jit = Jit::new("test_attributes", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::i32(ctx)?;
+    let pair = StructType::new(ctx, [i32, i32])?;
+    let ptr = i32.pointer(None)?;
+    let ft = FuncType::new(i32, [ptr, pair.as_ref().pointer(None)?])?;
+    let mut f = jit.module().define_function("attrs", ft)?;
+    f.add_attribute(AttributeIndex::Func, &Attribute::no_unwind(ctx)?);
+    f.add_attribute(AttributeIndex::Func, &Attribute::no_inline(ctx)?);
+    f.add_attribute(AttributeIndex::Param(0), &Attribute::no_alias(ctx)?);
+    f.add_attribute(
+        AttributeIndex::Param(0),
+        &Attribute::dereferenceable(ctx, 4)?,
+    );
+    f.add_attribute(AttributeIndex::Param(0), &Attribute::align(ctx, 4)?);
+    f.add_attribute(AttributeIndex::Param(1), &Attribute::byval(pair)?);
+
+    let nonnull = Attribute::new(ctx, AttrKind::NonNull)?;
+    assert_eq!(nonnull.enum_kind(), Some(AttrKind::NonNull.id() as i32));
+    let deref = Attribute::new(ctx, AttrKind::Dereferenceable(16))?;
+    assert_eq!(deref.enum_value(), Some(16));
+    let sret = Attribute::sret(pair)?;
+    assert!(sret.is_type());
+    assert_eq!(sret.type_value(), Some(pair.into()));
+    assert!(Attribute::new_type(ctx, "not_an_attribute", pair).is_err());
+    assert!(Attribute::align(ctx, 0).is_err());
+    assert!(Attribute::align(ctx, 12).is_err());
+    assert!(Attribute::new(ctx, AttrKind::Align(3)).is_err());
+    assert!(Attribute::stack_alignment(ctx, 6).is_err());
+    assert_eq!(Attribute::stack_alignment(ctx, 16)?.enum_value(), Some(16));
+
+    let attrs = f.attributes(AttributeIndex::Param(0));
+    assert_eq!(attrs.len(), 3);
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("i32* noalias align 4 dereferenceable(4),"));
+    assert!(ir.contains("byval({ i32, i32 })"));
+    assert!(ir.contains("attributes #0 = { noinline nounwind }"));
+
+    Ok(())
+}
+
+#[test]
+fn call_site() -> Result<(), Error> {
+    let jit = Jit::new("test_call_site", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::i32(ctx)?;
+    let ft = FuncType::new(i32, [i32, i32])?;
+    let mut add = jit.declare_function("fast_add", ft, |build, f| {
+        let x = build.add(f.param(0)?, f.param(1)?, "x")?;
+        build.ret(x)
+    })?;
+    add.set_call_conv(CallConv::LLVMFastCallConv);
+
+    let ft = FuncType::new(i32, [i32])?;
+    jit.declare_function("double", ft, |build, f| {
+        let x = f.param(0)?;
+        let mut call = build.call(add, [x, x], "call")?;
+        assert_eq!(call.call_conv(), CallConv::LLVMFastCallConv as u32);
+        call.set_call_conv(CallConv::LLVMCCallConv);
+        assert_eq!(call.call_conv(), CallConv::LLVMCCallConv as u32);
+        call.set_call_conv(add.call_conv());
+
+        assert_eq!(call.called_value()?, add.into());
+        assert_eq!(call.arg_operands()?, vec![x, x]);
+
+        call.add_attribute(AttributeIndex::Func, &Attribute::no_unwind(ctx)?);
+        call.add_attribute(AttributeIndex::Func, &Attribute::cold(ctx)?);
+        call.add_attribute(
+            AttributeIndex::Param(0),
+            &Attribute::new(ctx, AttrKind::NoUndef)?,
+        );
+        call.add_attribute(AttributeIndex::Func, &Attribute::new_string(ctx, "k", "v")?);
+        assert_eq!(call.attributes(AttributeIndex::Func).len(), 3);
+        call.remove_enum_attribute(AttributeIndex::Func, AttrKind::Cold.id());
+        call.remove_string_attribute(AttributeIndex::Func, "k");
+        assert_eq!(call.attributes(AttributeIndex::Func).len(), 1);
+        assert_eq!(call.attributes(AttributeIndex::Param(0)).len(), 1);
+
+        call.set_tail_call_kind(TailCallKind::MustTail);
+        assert_eq!(call.tail_call_kind(), TailCallKind::MustTail);
+        call.set_tail_call_kind(TailCallKind::NoTail);
+        assert_eq!(call.tail_call_kind(), TailCallKind::NoTail);
+        assert!(!call.is_tail_call());
+        call.set_tail_call_kind(TailCallKind::Tail);
+        assert_eq!(call.tail_call_kind(), TailCallKind::Tail);
+        assert!(call.is_tail_call());
+        build.ret(call)
+    })?;
+
+    // Indirect calls keep the default calling convention
+    let add_ptr = add.func_type()?.as_ref().pointer(None)?;
+    let ft = FuncType::new(i32, [add_ptr, i32])?;
+    jit.declare_function("indirect", ft, |build, f| {
+        let x = f.param(1)?;
+        let call = build.call(Func(f.param(0)?), [x, x], "call")?;
+        assert_eq!(call.call_conv(), CallConv::LLVMCCallConv as u32);
+        build.ret(call)
+    })?;
+
+    let ir = jit.module().to_string();
+    println!("{}", ir);
+    assert!(ir.contains("tail call fastcc i32 @fast_add(i32 noundef %0, i32 %0)"));
+    jit.module().verify()?;
+
+    let double: extern "C" fn(i32) -> i32 = unsafe { jit.engine().function("double")? };
+    assert_eq!(double(21), 42);
+
+    Ok(())
+}
+
+#[test]
+fn attribute_query() -> Result<(), Error> {
+    let jit = Jit::new("test_attribute_query", None)?;
+
+    let ctx = jit.context();
+    let i32 = Type::i32(ctx)?;
+    let ptr = i32.pointer(None)?;
+    let ft = FuncType::new(i32, [ptr])?;
+    let mut f = jit.module().define_function("query", ft)?;
+    f.add_attribute(AttributeIndex::Func, &Attribute::no_unwind(ctx)?);
+    f.add_attribute(
+        AttributeIndex::Func,
+        &Attribute::new_string(ctx, "frame-pointer", "all")?,
+    );
+    f.add_attribute(
+        AttributeIndex::Param(0),
+        &Attribute::dereferenceable(ctx, 8)?,
+    );
+    f.add_attribute(AttributeIndex::Param(0), &Attribute::element_type(i32)?);
+    f.add_attribute(
+        AttributeIndex::Return,
+        &Attribute::new(ctx, AttrKind::NoUndef)?,
+    );
+
+    assert_eq!(f.attribute_count(AttributeIndex::Func), 2);
+    assert_eq!(f.attribute_count(AttributeIndex::Param(0)), 2);
+    assert_eq!(f.attribute_count(AttributeIndex::Return), 1);
+    assert!(f.has_attribute(AttributeIndex::Func, AttrKind::NoUnwind));
+    assert!(!f.has_attribute(AttributeIndex::Func, AttrKind::Cold));
+    assert!(f.has_attribute(AttributeIndex::Param(0), AttrKind::Dereferenceable(0)));
+
+    let deref = f
+        .enum_attribute(AttributeIndex::Param(0), AttrKind::Dereferenceable(0))
+        .unwrap();
+    assert_eq!(deref, Attribute::dereferenceable(ctx, 8)?);
+    assert_eq!(deref.to_string(), "dereferenceable(8)");
+
+    let fp = f
+        .string_attribute(AttributeIndex::Func, "frame-pointer")
+        .unwrap();
+    assert_eq!(fp.to_string(), "\"frame-pointer\"=\"all\"");
+    assert!(f
+        .string_attribute(AttributeIndex::Func, "missing")
+        .is_none());
+
+    let mut names: Vec<String> = f
+        .attributes(AttributeIndex::Param(0))
+        .iter()
+        .map(|x| x.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["dereferenceable(8)", "elementtype(i32)"]);
+    assert_eq!(Attribute::no_unwind(ctx)?.to_string(), "nounwind");
+
+    // Every attribute kind known to LLVM has a name, and every name maps back to its kind, the
+    // "last" kind is one past the end
+    let end = unsafe { llvm::core::LLVMGetLastEnumAttributeKind() };
+    assert_eq!(attribute::ATTRIBUTE_KINDS.len(), end as usize - 1);
+    for kind in 1..end {
+        let name = attribute::ATTRIBUTE_KINDS[&kind];
+        let id = unsafe {
+            llvm::core::LLVMGetEnumAttributeKindForName(name.as_ptr() as *const c_char, name.len())
+        };
+        assert_eq!(id, kind, "{}", name);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn diagnostics() -> Result<(), Error> {
+    let ctx = Context::new()?;
+    let diagnostics = ctx.collect_diagnostics();
+
+    let i32 = Type::i32(&ctx)?;
+    let a = Module::new(&ctx, "a")?;
+    let b = Module::new(&ctx, "b")?;
+    for (module, value) in [(&a, 1), (&b, 2)] {
+        module.add_module_flag(
+            ModuleFlagBehavior::LLVMModuleFlagBehaviorWarning,
+            "test-flag",
+            Const::int(i32, value)?.as_ref().to_metadata(),
+        );
+    }
+    assert!(!a.link(&b));
+
+    let d = diagnostics.take();
+    println!("{:?}", d);
+    assert_eq!(d.len(), 1);
+    assert_eq!(d[0].severity, DiagnosticSeverity::LLVMDSWarning);
+    assert!(d[0].message.contains("test-flag"));
+    assert!(!diagnostics.has_errors());
+    assert!(diagnostics.take().is_empty());
+
+    let count = std::rc::Rc::new(std::cell::Cell::new(0));
+    let c = count.clone();
+    ctx.set_diagnostic_handler(move |_| c.set(c.get() + 1));
+    let c = Module::new(&ctx, "c")?;
+    c.add_module_flag(
+        ModuleFlagBehavior::LLVMModuleFlagBehaviorError,
+        "test-flag",
+        Const::int(i32, 3)?.as_ref().to_metadata(),
+    );
+    assert!(a.link(&c));
+    ctx.clear_diagnostic_handler();
+    assert_eq!(count.get(), 1);
+
+    // A panic in the handler is re-raised after LLVM returns instead of aborting
+    ctx.set_diagnostic_handler(|_| panic!("diagnostic"));
+    let d = Module::new(&ctx, "d")?;
+    d.add_module_flag(
+        ModuleFlagBehavior::LLVMModuleFlagBehaviorError,
+        "test-flag",
+        Const::int(i32, 4)?.as_ref().to_metadata(),
+    );
+    let e = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| a.link(&d))).unwrap_err();
+    assert_eq!(e.downcast_ref::<&str>(), Some(&"diagnostic"));
+    ctx.clear_diagnostic_handler();
+
+    // The legacy pass manager yields after each function
+    let ft = FuncType::new(i32, [])?;
+    let f = a.define_function("yield", ft)?;
+    let build = Builder::new(&ctx)?;
+    build.function_body(f, |build, _| build.ret(Const::int(i32, 0)?))?;
+    let fpm = FuncPassManager::new(&a)?;
+
+    let count = std::rc::Rc::new(std::cell::Cell::new(0));
+    let c = count.clone();
+    ctx.set_yield_callback(move || c.set(c.get() + 1));
+    fpm.run(&f);
+    fpm.run(&f);
+    assert_eq!(count.get(), 2);
+
+    ctx.set_yield_callback(|| panic!("yield"));
+    let e = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| fpm.run(&f))).unwrap_err();
+    assert_eq!(e.downcast_ref::<&str>(), Some(&"yield"));
+    ctx.clear_yield_callback();
+    fpm.run(&f);
+    assert_eq!(count.get(), 2);
 
     Ok(())
 }
@@ -117,17 +1652,17 @@ fn test_add_symbol() -> Result<(), Error> {
 
     let i32 = Type::int(jit.context(), 32)?;
 
-    let testing123_t = FuncType::new(i32, &[])?;
-    let testing1234_t = FuncType::new(i32, &[])?;
+    let testing123_t = FuncType::new(i32, [])?;
+    let testing1234_t = FuncType::new(i32, [])?;
     let testing123 = jit.define_function("testing123", testing123_t)?;
     let testing1234 = jit.define_function("testing1234", testing1234_t)?;
 
     jit.declare_function("testing", testing123.func_type()?, |build, _| {
-        build.ret(build.call(testing123, &[], "call")?)
+        build.ret(build.call(testing123, [], "call")?)
     })?;
 
     jit.declare_function("testing1", testing1234.func_type()?, |build, _| {
-        build.ret(build.call(testing1234, &[], "call")?)
+        build.ret(build.call(testing1234, [], "call")?)
     })?;
 
     let testing: extern "C" fn() -> i32 = unsafe { jit.engine().function("testing")? };
@@ -179,19 +1714,19 @@ fn test_rust_struct() -> Result<(), Error> {
 
     let i64 = Type::int(ctx, 64)?;
 
-    let mk_test = jit.define_function("mk_test", FuncType::new(ptr, &[i64, i64])?)?;
-    let test_add = jit.define_function("test_add", FuncType::new(i64, &[ptr])?)?;
-    let test_free = jit.define_function("test_free", FuncType::new(Type::void(ctx)?, &[ptr])?)?;
+    let mk_test = jit.define_function("mk_test", FuncType::new(ptr, [i64, i64])?)?;
+    let test_add = jit.define_function("test_add", FuncType::new(i64, [ptr])?)?;
+    let test_free = jit.define_function("test_free", FuncType::new(Type::void(ctx)?, [ptr])?)?;
 
-    let run_test_t = FuncType::new(i64, &[i64, i64])?;
+    let run_test_t = FuncType::new(i64, [i64, i64])?;
 
     jit.declare_function("run_test", run_test_t, |build, f| {
         let a = f.param(0)?;
         let b = f.param(1)?;
 
-        let x = build.call(mk_test, &[a, b], "mk_test")?;
-        let y = build.call(test_add, &[x.into()], "test_add")?;
-        build.call(test_free, &[x.into()], "free")?;
+        let x = build.call(mk_test, [a, b], "mk_test")?;
+        let y = build.call(test_add, [x.into()], "test_add")?;
+        build.call(test_free, [x.into()], "free")?;
 
         build.ret(y)
     })?;
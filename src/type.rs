@@ -1,7 +1,7 @@
 use crate::*;
 
 /// LLVMType wrapper
-#[derive(Copy)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct Type<'a>(NonNull<llvm::LLVMType>, PhantomData<&'a ()>);
 
 llvm_inner_impl!(Type<'a>, llvm::LLVMType);
@@ -10,15 +10,9 @@ llvm_inner_impl!(Type<'a>, llvm::LLVMType);
 pub type TypeKind = llvm::LLVMTypeKind;
 
 /// Function type
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct FuncType<'a>(pub(crate) Type<'a>);
 
-impl<'a> Clone for Type<'a> {
-    fn clone(&self) -> Type<'a> {
-        Type(self.0, PhantomData)
-    }
-}
-
 impl<'a> AsRef<Type<'a>> for Type<'a> {
     fn as_ref(&self) -> &Type<'a> {
         self
@@ -38,7 +32,7 @@ impl<'a> From<FuncType<'a>> for Type<'a> {
 }
 
 /// Struct type
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct StructType<'a>(pub(crate) Type<'a>);
 
 impl<'a> AsRef<Type<'a>> for StructType<'a> {
@@ -53,8 +47,92 @@ impl<'a> From<StructType<'a>> for Type<'a> {
     }
 }
 
+/// Floating point type kinds, used by `TypeDesc::Float`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FloatKind {
+    /// 16-bit IEEE float
+    Half,
+    /// 16-bit brain float
+    BFloat,
+    /// 32-bit IEEE float
+    Float,
+    /// 64-bit IEEE float
+    Double,
+    /// 80-bit x87 float
+    X86Fp80,
+    /// 128-bit IEEE float
+    Fp128,
+    /// 128-bit PowerPC double-double
+    PpcFp128,
+}
+
+/// Structured description of a `Type`, see `Type::describe` and `Type::from_desc`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TypeDesc {
+    /// Void type
+    Void,
+    /// Label type
+    Label,
+    /// Metadata type
+    Metadata,
+    /// Token type
+    Token,
+    /// x86 MMX type
+    X86Mmx,
+    /// x86 AMX type
+    X86Amx,
+    /// Integer with the given bit width
+    Int(usize),
+    /// Floating point type
+    Float(FloatKind),
+    /// Pointer to `element` in the given address space
+    Pointer {
+        /// Pointee type
+        element: Box<TypeDesc>,
+        /// Address space
+        address_space: usize,
+    },
+    /// Array of elements with the given length
+    Array(Box<TypeDesc>, usize),
+    /// Fixed vector of elements with the given length
+    Vector(Box<TypeDesc>, usize),
+    /// Scalable vector of elements with the given minimum length
+    ScalableVector(Box<TypeDesc>, usize),
+    /// Struct type, `fields` is `None` for opaque structs and for recursive references to a named
+    /// struct that is already being described
+    Struct {
+        /// Name of the struct, `None` for literal structs
+        name: Option<String>,
+        /// Struct fields
+        fields: Option<Vec<TypeDesc>>,
+        /// Packed struct
+        packed: bool,
+    },
+    /// Function type
+    Function {
+        /// Return type
+        return_type: Box<TypeDesc>,
+        /// Parameter types
+        params: Vec<TypeDesc>,
+        /// Variable number of arguments
+        var_arg: bool,
+    },
+}
+
+/// Rust types with an equivalent LLVM type, used by `Type::of`
+///
+/// `#[repr(C)]` structs can implement this using `#[derive(LLVMType)]` from the `llama-derive`
+/// crate
 pub trait LLVMType<'a> {
+    /// Get the LLVM type, `usize` and `isize` use the pointer width of the host
     fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error>;
+
+    /// Get the LLVM type for the target described by `data`, this only differs from `llvm_type`
+    /// for types containing `usize` or `isize`
+    fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+        let _ = data;
+        Self::llvm_type(ctx)
+    }
 }
 
 impl<'a> Type<'a> {
@@ -64,11 +142,21 @@ impl<'a> Type<'a> {
         Ok(Type(t, PhantomData))
     }
 
-    /// Allows for conversion between Rust/LLVM types
+    /// Allows for conversion between Rust/LLVM types, `usize` and `isize` use the pointer width of
+    /// the host, which is only correct when JIT compiling
     pub fn of<T: LLVMType<'a>>(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
         T::llvm_type(ctx)
     }
 
+    /// Convert a Rust type to an LLVM type for the target described by `data`, `usize` and
+    /// `isize` use the pointer width of the target
+    pub fn of_target<T: LLVMType<'a>>(
+        ctx: &Context<'a>,
+        data: &TargetData<'a>,
+    ) -> Result<Type<'a>, Error> {
+        T::llvm_target_type(ctx, data)
+    }
+
     /// Get type by name
     pub fn by_name(ctx: &'a Context, name: impl AsRef<str>) -> Result<Type<'a>, Error> {
         ctx.type_by_name(name)
@@ -175,6 +263,39 @@ impl<'a> Type<'a> {
         unsafe { Self::from_inner(llvm::core::LLVMFP128TypeInContext(ctx.llvm())) }
     }
 
+    /// Create bfloat type
+    pub fn bfloat(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        unsafe { Self::from_inner(llvm::core::LLVMBFloatTypeInContext(ctx.llvm())) }
+    }
+
+    /// Create x86 FP80 type
+    pub fn x86_fp80(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        unsafe { Self::from_inner(llvm::core::LLVMX86FP80TypeInContext(ctx.llvm())) }
+    }
+
+    /// Create PPC FP128 type
+    pub fn ppc_fp128(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        unsafe { Self::from_inner(llvm::core::LLVMPPCFP128TypeInContext(ctx.llvm())) }
+    }
+
+    /// Create x86AMX type
+    pub fn x86_amx(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        unsafe { Self::from_inner(llvm::core::LLVMX86AMXTypeInContext(ctx.llvm())) }
+    }
+
+    /// Create floating point type of the given kind
+    pub fn float_kind(ctx: &Context<'a>, kind: FloatKind) -> Result<Type<'a>, Error> {
+        match kind {
+            FloatKind::Half => Self::half(ctx),
+            FloatKind::BFloat => Self::bfloat(ctx),
+            FloatKind::Float => Self::float(ctx),
+            FloatKind::Double => Self::double(ctx),
+            FloatKind::X86Fp80 => Self::x86_fp80(ctx),
+            FloatKind::Fp128 => Self::fp128(ctx),
+            FloatKind::PpcFp128 => Self::ppc_fp128(ctx),
+        }
+    }
+
     /// Get type of element
     pub fn element_type(self) -> Result<Type<'a>, Error> {
         let t = unsafe { llvm::core::LLVMGetElementType(self.llvm()) };
@@ -218,6 +339,16 @@ impl<'a> Type<'a> {
         unsafe { Self::from_inner(llvm::core::LLVMVectorType(self.llvm(), count as c_uint)) }
     }
 
+    /// Make scalable vector type
+    pub fn scalable_vector(self, count: usize) -> Result<Type<'a>, Error> {
+        unsafe {
+            Self::from_inner(llvm::core::LLVMScalableVectorType(
+                self.llvm(),
+                count as c_uint,
+            ))
+        }
+    }
+
     /// Make array type
     pub fn array(self, count: usize) -> Result<Type<'a>, Error> {
         unsafe { Self::from_inner(llvm::core::LLVMArrayType(self.llvm(), count as c_uint)) }
@@ -240,12 +371,178 @@ impl<'a> Type<'a> {
 
     /// Get vector length
     pub fn vector_len(self) -> usize {
-        unsafe { llvm::core::LLVMGetArrayLength(self.llvm()) as usize }
+        unsafe { llvm::core::LLVMGetVectorSize(self.llvm()) as usize }
     }
 
     /// Get pointer address space
     pub fn pointer_address_space(self) -> usize {
-        unsafe { llvm::core::LLVMGetArrayLength(self.llvm()) as usize }
+        unsafe { llvm::core::LLVMGetPointerAddressSpace(self.llvm()) as usize }
+    }
+
+    /// Get a structured description of the type
+    pub fn describe(self) -> Result<TypeDesc, Error> {
+        self.describe_inner(&mut Vec::new())
+    }
+
+    fn describe_inner(self, named: &mut Vec<Type<'a>>) -> Result<TypeDesc, Error> {
+        let desc = match self.kind() {
+            TypeKind::LLVMVoidTypeKind => TypeDesc::Void,
+            TypeKind::LLVMLabelTypeKind => TypeDesc::Label,
+            TypeKind::LLVMMetadataTypeKind => TypeDesc::Metadata,
+            TypeKind::LLVMTokenTypeKind => TypeDesc::Token,
+            TypeKind::LLVMX86_MMXTypeKind => TypeDesc::X86Mmx,
+            TypeKind::LLVMX86_AMXTypeKind => TypeDesc::X86Amx,
+            TypeKind::LLVMIntegerTypeKind => TypeDesc::Int(self.int_width()),
+            TypeKind::LLVMHalfTypeKind => TypeDesc::Float(FloatKind::Half),
+            TypeKind::LLVMBFloatTypeKind => TypeDesc::Float(FloatKind::BFloat),
+            TypeKind::LLVMFloatTypeKind => TypeDesc::Float(FloatKind::Float),
+            TypeKind::LLVMDoubleTypeKind => TypeDesc::Float(FloatKind::Double),
+            TypeKind::LLVMX86_FP80TypeKind => TypeDesc::Float(FloatKind::X86Fp80),
+            TypeKind::LLVMFP128TypeKind => TypeDesc::Float(FloatKind::Fp128),
+            TypeKind::LLVMPPC_FP128TypeKind => TypeDesc::Float(FloatKind::PpcFp128),
+            TypeKind::LLVMPointerTypeKind => TypeDesc::Pointer {
+                element: Box::new(self.element_type()?.describe_inner(named)?),
+                address_space: self.pointer_address_space(),
+            },
+            TypeKind::LLVMArrayTypeKind => TypeDesc::Array(
+                Box::new(self.element_type()?.describe_inner(named)?),
+                self.array_len(),
+            ),
+            TypeKind::LLVMVectorTypeKind => TypeDesc::Vector(
+                Box::new(self.element_type()?.describe_inner(named)?),
+                self.vector_len(),
+            ),
+            TypeKind::LLVMScalableVectorTypeKind => TypeDesc::ScalableVector(
+                Box::new(self.element_type()?.describe_inner(named)?),
+                self.vector_len(),
+            ),
+            TypeKind::LLVMStructTypeKind => {
+                let t = StructType(self);
+                let name = t.name()?.map(String::from);
+                let fields = if t.is_opaque() || named.contains(&self) {
+                    None
+                } else {
+                    if name.is_some() {
+                        named.push(self);
+                    }
+                    let fields = t
+                        .fields()
+                        .into_iter()
+                        .map(|x| x.describe_inner(named))
+                        .collect::<Result<Vec<_>, _>>();
+                    if name.is_some() {
+                        named.pop();
+                    }
+                    Some(fields?)
+                };
+                TypeDesc::Struct {
+                    name,
+                    fields,
+                    packed: t.is_packed(),
+                }
+            }
+            TypeKind::LLVMFunctionTypeKind => {
+                let t = FuncType(self);
+                TypeDesc::Function {
+                    return_type: Box::new(t.return_type()?.describe_inner(named)?),
+                    params: t
+                        .params()
+                        .into_iter()
+                        .map(|x| x.describe_inner(named))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    var_arg: t.is_var_arg(),
+                }
+            }
+        };
+        Ok(desc)
+    }
+
+    /// Create a type from a structured description
+    ///
+    /// Named structs are looked up by name first, a new named struct is only created if the name
+    /// isn't already defined in `ctx`. An existing opaque struct gets the body from `desc`, an
+    /// existing struct with a different body returns `Error::StructMismatch`
+    pub fn from_desc(ctx: &Context<'a>, desc: &TypeDesc) -> Result<Type<'a>, Error> {
+        match desc {
+            TypeDesc::Void => Self::void(ctx),
+            TypeDesc::Label => Self::label(ctx),
+            TypeDesc::Metadata => Self::metadata(ctx),
+            TypeDesc::Token => Self::token(ctx),
+            TypeDesc::X86Mmx => Self::x86_mmx(ctx),
+            TypeDesc::X86Amx => Self::x86_amx(ctx),
+            TypeDesc::Int(bits) => Self::int(ctx, *bits),
+            TypeDesc::Float(kind) => Self::float_kind(ctx, *kind),
+            TypeDesc::Pointer {
+                element,
+                address_space,
+            } => Self::from_desc(ctx, element)?.pointer(Some(*address_space)),
+            TypeDesc::Array(element, len) => Self::from_desc(ctx, element)?.array(*len),
+            TypeDesc::Vector(element, len) => Self::from_desc(ctx, element)?.vector(*len),
+            TypeDesc::ScalableVector(element, len) => {
+                Self::from_desc(ctx, element)?.scalable_vector(*len)
+            }
+            TypeDesc::Struct {
+                name: Some(name),
+                fields,
+                packed,
+            } => {
+                let mut t = match ctx.type_by_name(name) {
+                    Ok(t) => t.to_struct_type()?,
+                    Err(_) => StructType::new_with_name(ctx, name)?,
+                };
+
+                let fields = match fields {
+                    Some(fields) => fields
+                        .iter()
+                        .map(|x| Self::from_desc(ctx, x))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => return Ok(t.into()),
+                };
+
+                if t.is_opaque() {
+                    if *packed {
+                        t.set_body_packed(fields)
+                    } else {
+                        t.set_body(fields)
+                    }
+                } else if t.is_packed() != *packed || t.fields() != fields {
+                    return Err(Error::StructMismatch(name.clone()));
+                }
+                Ok(t.into())
+            }
+            TypeDesc::Struct {
+                name: None,
+                fields,
+                packed,
+            } => {
+                let fields = fields
+                    .iter()
+                    .flatten()
+                    .map(|x| Self::from_desc(ctx, x))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if *packed {
+                    Ok(StructType::new_packed(ctx, fields)?.into())
+                } else {
+                    Ok(StructType::new(ctx, fields)?.into())
+                }
+            }
+            TypeDesc::Function {
+                return_type,
+                params,
+                var_arg,
+            } => {
+                let return_type = Self::from_desc(ctx, return_type)?;
+                let params = params
+                    .iter()
+                    .map(|x| Self::from_desc(ctx, x))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if *var_arg {
+                    Ok(FuncType::new_var_arg(return_type, params)?.into())
+                } else {
+                    Ok(FuncType::new(return_type, params)?.into())
+                }
+            }
+        }
     }
 
     /// Get alignment of type
@@ -407,12 +704,10 @@ impl<'a> StructType<'a> {
     /// Get all fields
     pub fn fields(self) -> Vec<Type<'a>> {
         let len = self.field_count();
-        let mut ptr = std::ptr::null_mut();
+        let mut data = vec![std::ptr::null_mut(); len];
 
-        unsafe { llvm::core::LLVMGetStructElementTypes(self.as_ref().llvm(), &mut ptr) }
-        let slice = unsafe { std::slice::from_raw_parts_mut(ptr, len) };
-        slice
-            .iter_mut()
+        unsafe { llvm::core::LLVMGetStructElementTypes(self.as_ref().llvm(), data.as_mut_ptr()) }
+        data.into_iter()
             .map(|x| Type::from_inner(x).unwrap())
             .collect()
     }
@@ -450,6 +745,12 @@ impl<'a> std::fmt::Display for Type<'a> {
     }
 }
 
+impl<'a> std::fmt::Debug for Type<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self)
+    }
+}
+
 impl<'a> LLVMType<'a> for u8 {
     fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
         Type::int(ctx, 8)
@@ -532,10 +833,168 @@ impl<'a, T: LLVMType<'a>> LLVMType<'a> for *const T {
     fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
         T::llvm_type(ctx)?.pointer(None)
     }
+
+    fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+        T::llvm_target_type(ctx, data)?.pointer(None)
+    }
 }
 
 impl<'a, T: LLVMType<'a>> LLVMType<'a> for *mut T {
     fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
         T::llvm_type(ctx)?.pointer(None)
     }
+
+    fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+        T::llvm_target_type(ctx, data)?.pointer(None)
+    }
+}
+
+/// `bool` is represented as `i1`, the C ABI passes `bool` arguments and return values as
+/// zero-extended bytes so functions called across FFI need the `zeroext` attribute on those
+/// parameters, see `AttrKind::ZExt`
+impl<'a> LLVMType<'a> for bool {
+    fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        Type::i1(ctx)
+    }
+}
+
+/// `usize` matches the pointer width of the host, or of the target when using `Type::of_target`
+impl<'a> LLVMType<'a> for usize {
+    fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        Type::int(ctx, std::mem::size_of::<usize>() * 8)
+    }
+
+    fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+        data.int_ptr_type(ctx)
+    }
+}
+
+/// `isize` matches the pointer width of the host, or of the target when using `Type::of_target`
+impl<'a> LLVMType<'a> for isize {
+    fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        Type::int(ctx, std::mem::size_of::<isize>() * 8)
+    }
+
+    fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+        data.int_ptr_type(ctx)
+    }
+}
+
+impl<'a, T: LLVMType<'a>, const N: usize> LLVMType<'a> for [T; N] {
+    fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+        T::llvm_type(ctx)?.array(N)
+    }
+
+    fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+        T::llvm_target_type(ctx, data)?.array(N)
+    }
 }
+
+macro_rules! tuple_llvm_type {
+    ($($t:ident),+) => {
+        /// Tuples are converted to literal structs with fields in declaration order, Rust does
+        /// not guarantee that tuple layouts match
+        impl<'a, $($t: LLVMType<'a>),+> LLVMType<'a> for ($($t,)+) {
+            fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+                let fields = [$($t::llvm_type(ctx)?),+];
+                Ok(StructType::new(ctx, fields)?.into())
+            }
+
+            fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+                let fields = [$($t::llvm_target_type(ctx, data)?),+];
+                Ok(StructType::new(ctx, fields)?.into())
+            }
+        }
+    };
+}
+
+tuple_llvm_type!(A);
+tuple_llvm_type!(A, B);
+tuple_llvm_type!(A, B, C);
+tuple_llvm_type!(A, B, C, D);
+tuple_llvm_type!(A, B, C, D, E);
+tuple_llvm_type!(A, B, C, D, E, F);
+tuple_llvm_type!(A, B, C, D, E, F, G);
+tuple_llvm_type!(A, B, C, D, E, F, G, H);
+
+macro_rules! fn_llvm_type {
+    ($($t:ident),*) => {
+        impl<'a, R: LLVMType<'a>, $($t: LLVMType<'a>),*> LLVMType<'a> for extern "C" fn($($t),*) -> R {
+            fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+                let params: Vec<Type<'a>> = vec![$($t::llvm_type(ctx)?),*];
+                FuncType::new(R::llvm_type(ctx)?, params)?.as_ref().pointer(None)
+            }
+
+            fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+                let params: Vec<Type<'a>> = vec![$($t::llvm_target_type(ctx, data)?),*];
+                FuncType::new(R::llvm_target_type(ctx, data)?, params)?.as_ref().pointer(None)
+            }
+        }
+
+        impl<'a, R: LLVMType<'a>, $($t: LLVMType<'a>),*> LLVMType<'a> for unsafe extern "C" fn($($t),*) -> R {
+            fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+                <extern "C" fn($($t),*) -> R>::llvm_type(ctx)
+            }
+
+            fn llvm_target_type(ctx: &Context<'a>, data: &TargetData<'a>) -> Result<Type<'a>, Error> {
+                <extern "C" fn($($t),*) -> R>::llvm_target_type(ctx, data)
+            }
+        }
+    };
+}
+
+fn_llvm_type!();
+fn_llvm_type!(A);
+fn_llvm_type!(A, B);
+fn_llvm_type!(A, B, C);
+fn_llvm_type!(A, B, C, D);
+fn_llvm_type!(A, B, C, D, E);
+fn_llvm_type!(A, B, C, D, E, F);
+fn_llvm_type!(A, B, C, D, E, F, G);
+fn_llvm_type!(A, B, C, D, E, F, G, H);
+
+macro_rules! vector_llvm_type {
+    ($($v:ty => [$t:ty; $n:literal]),*$(,)?) => {
+        $(
+            impl<'a> LLVMType<'a> for $v {
+                fn llvm_type(ctx: &Context<'a>) -> Result<Type<'a>, Error> {
+                    <$t>::llvm_type(ctx)?.vector($n)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+macro_rules! x86_vector_llvm_type {
+    ($arch:ident) => {
+        vector_llvm_type!(
+            std::arch::$arch::__m128 => [f32; 4],
+            std::arch::$arch::__m128d => [f64; 2],
+            std::arch::$arch::__m128i => [i64; 2],
+            std::arch::$arch::__m256 => [f32; 8],
+            std::arch::$arch::__m256d => [f64; 4],
+            std::arch::$arch::__m256i => [i64; 4],
+        );
+    };
+}
+
+#[cfg(target_arch = "x86")]
+x86_vector_llvm_type!(x86);
+
+#[cfg(target_arch = "x86_64")]
+x86_vector_llvm_type!(x86_64);
+
+#[cfg(target_arch = "aarch64")]
+vector_llvm_type!(
+    std::arch::aarch64::float32x4_t => [f32; 4],
+    std::arch::aarch64::float64x2_t => [f64; 2],
+    std::arch::aarch64::int8x16_t => [i8; 16],
+    std::arch::aarch64::int16x8_t => [i16; 8],
+    std::arch::aarch64::int32x4_t => [i32; 4],
+    std::arch::aarch64::int64x2_t => [i64; 2],
+    std::arch::aarch64::uint8x16_t => [u8; 16],
+    std::arch::aarch64::uint16x8_t => [u16; 8],
+    std::arch::aarch64::uint32x4_t => [u32; 4],
+    std::arch::aarch64::uint64x2_t => [u64; 2],
+);
@@ -1,7 +1,7 @@
 use crate::*;
 
 /// LLVM Value wrapper
-#[derive(Copy)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct Value<'a>(NonNull<llvm::LLVMValue>, PhantomData<&'a ()>);
 
 llvm_inner_impl!(Value<'a>, llvm::LLVMValue);
@@ -27,10 +27,7 @@ impl AttributeIndex {
         match self {
             AttributeIndex::Return => llvm::LLVMAttributeReturnIndex,
             AttributeIndex::Param(index) => {
-                assert!(
-                    index <= u32::max_value() - 2,
-                    "Param index must be <= u32::max_value() - 2"
-                );
+                assert!(index <= u32::MAX - 2, "Param index must be <= u32::MAX - 2");
 
                 index + 1
             }
@@ -45,12 +42,6 @@ impl<'a> AsRef<Value<'a>> for Value<'a> {
     }
 }
 
-impl<'a> Clone for Value<'a> {
-    fn clone(&self) -> Value<'a> {
-        Value(self.0, PhantomData)
-    }
-}
-
 impl<'a> Value<'a> {
     /// Wrap an LLVMValue pointer
     pub fn from_inner(ptr: *mut llvm::LLVMValue) -> Result<Value<'a>, Error> {
@@ -93,6 +84,21 @@ impl<'a> Value<'a> {
         self.type_of()?.into_context()
     }
 
+    /// Get the module containing a global value, returns `Error::InvalidGlobal` for any other
+    /// kind of value
+    pub fn module(self) -> Result<Module<'a>, Error> {
+        if unsafe { llvm::core::LLVMIsAGlobalValue(self.llvm()) }.is_null() {
+            return Err(Error::InvalidGlobal);
+        }
+
+        let m = unsafe { wrap_inner(llvm::core::LLVMGetGlobalParent(self.llvm()))? };
+        Ok(Module(
+            m,
+            std::sync::atomic::AtomicBool::new(false),
+            PhantomData,
+        ))
+    }
+
     /// Get the name of a value
     pub fn name(self) -> Result<&'a str, Error> {
         let mut size = 0;
@@ -161,6 +167,54 @@ impl<'a> Value<'a> {
         unsafe { llvm::core::LLVMSetThreadLocal(self.llvm(), if b { 1 } else { 0 }) }
     }
 
+    /// Get the section of a global value
+    pub fn section(self) -> Option<&'a str> {
+        unsafe {
+            let s = llvm::core::LLVMGetSection(self.llvm());
+            if s.is_null() {
+                return None;
+            }
+            let s = std::slice::from_raw_parts(s as *const u8, strlen(s));
+            std::str::from_utf8(s).ok()
+        }
+    }
+
+    /// Place a global value in the given section
+    pub fn set_section(&mut self, section: impl AsRef<str>) {
+        let section = cstr!(section.as_ref());
+        unsafe { llvm::core::LLVMSetSection(self.llvm(), section.as_ptr()) }
+    }
+
+    /// Get the comdat of a global object
+    pub fn comdat(self) -> Result<Comdat<'a>, Error> {
+        unsafe { Comdat::from_inner(llvm::comdat::LLVMGetComdat(self.llvm())) }
+    }
+
+    /// Assign a global object to a comdat
+    pub fn set_comdat(&mut self, comdat: Comdat<'a>) {
+        unsafe { llvm::comdat::LLVMSetComdat(self.llvm(), comdat.llvm()) }
+    }
+
+    /// Get the unnamed_addr attribute of a global value
+    pub fn unnamed_addr(self) -> UnnamedAddr {
+        unsafe { llvm::core::LLVMGetUnnamedAddress(self.llvm()) }
+    }
+
+    /// Set the unnamed_addr attribute of a global value
+    pub fn set_unnamed_addr(&mut self, a: UnnamedAddr) {
+        unsafe { llvm::core::LLVMSetUnnamedAddress(self.llvm(), a) }
+    }
+
+    /// Get the DLL storage class of a global value
+    pub fn dll_storage_class(self) -> DLLStorageClass {
+        unsafe { llvm::core::LLVMGetDLLStorageClass(self.llvm()) }
+    }
+
+    /// Set the DLL storage class of a global value
+    pub fn set_dll_storage_class(&mut self, c: DLLStorageClass) {
+        unsafe { llvm::core::LLVMSetDLLStorageClass(self.llvm(), c) }
+    }
+
     /// Returns true when the value is a `Const`
     pub fn is_const(self) -> bool {
         unsafe { llvm::core::LLVMIsConstant(self.llvm()) == 1 }
@@ -208,8 +262,14 @@ impl<'a> std::fmt::Display for Value<'a> {
     }
 }
 
+impl<'a> std::fmt::Debug for Value<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}", self)
+    }
+}
+
 /// Functions
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Func<'a>(pub Value<'a>);
 
 impl<'a> AsRef<Value<'a>> for Func<'a> {
@@ -371,18 +431,22 @@ impl<'a> Func<'a> {
         }
     }
 
-    /// Get all attributes
-    pub fn attributes(self, index: usize) -> Vec<Attribute<'a>> {
-        let count = unsafe {
-            llvm::core::LLVMGetAttributeCountAtIndex(self.as_ref().llvm(), index as c_uint)
-        };
+    /// Get the number of attributes at `index`
+    pub fn attribute_count(self, index: AttributeIndex) -> usize {
+        unsafe {
+            llvm::core::LLVMGetAttributeCountAtIndex(self.as_ref().llvm(), index.get_index())
+                as usize
+        }
+    }
 
-        let mut output = vec![std::ptr::null_mut(); count as usize];
+    /// Get all attributes at `index`
+    pub fn attributes(self, index: AttributeIndex) -> Vec<Attribute<'a>> {
+        let mut output = vec![std::ptr::null_mut(); self.attribute_count(index)];
 
         unsafe {
             llvm::core::LLVMGetAttributesAtIndex(
                 self.as_ref().llvm(),
-                index as c_uint,
+                index.get_index(),
                 output.as_mut_ptr(),
             );
         }
@@ -393,6 +457,42 @@ impl<'a> Func<'a> {
             .collect()
     }
 
+    /// Get the enum attribute of the given kind at `index`, the value stored in `kind` is ignored
+    pub fn enum_attribute(self, index: AttributeIndex, kind: AttrKind) -> Option<Attribute<'a>> {
+        unsafe {
+            Attribute::from_inner(llvm::core::LLVMGetEnumAttributeAtIndex(
+                self.as_ref().llvm(),
+                index.get_index(),
+                kind.id(),
+            ))
+            .ok()
+        }
+    }
+
+    /// Get the string attribute with the given key at `index`
+    pub fn string_attribute(
+        self,
+        index: AttributeIndex,
+        k: impl AsRef<str>,
+    ) -> Option<Attribute<'a>> {
+        let len = k.as_ref().len();
+        let k = cstr!(k.as_ref());
+        unsafe {
+            Attribute::from_inner(llvm::core::LLVMGetStringAttributeAtIndex(
+                self.as_ref().llvm(),
+                index.get_index(),
+                k.as_ptr(),
+                len as c_uint,
+            ))
+            .ok()
+        }
+    }
+
+    /// Returns true when an enum attribute of the given kind is set at `index`
+    pub fn has_attribute(self, index: AttributeIndex, kind: AttrKind) -> bool {
+        self.enum_attribute(index, kind).is_some()
+    }
+
     /// Create specified uniqued inline asm string (intel syntax)
     pub fn inline_asm_intel(
         t: impl AsRef<Type<'a>>,
@@ -479,12 +579,10 @@ impl<'a> Func<'a> {
     /// Get a list of all basic blocks
     pub fn basic_blocks(self) -> Vec<BasicBlock<'a>> {
         let count = self.count_basic_blocks();
-        let ptr = std::ptr::null_mut();
-        unsafe { llvm::core::LLVMGetBasicBlocks(self.0.llvm(), ptr) }
-        let slice = unsafe { std::slice::from_raw_parts(ptr, count) };
-        slice
-            .iter()
-            .map(|x| BasicBlock::from_inner(*x).unwrap())
+        let mut data = vec![std::ptr::null_mut(); count];
+        unsafe { llvm::core::LLVMGetBasicBlocks(self.0.llvm(), data.as_mut_ptr()) }
+        data.into_iter()
+            .map(|x| BasicBlock::from_inner(x).unwrap())
             .collect()
     }
 
@@ -503,8 +601,118 @@ impl<'a> Func<'a> {
         BasicBlock::from_inner(unsafe { llvm::core::LLVMGetEntryBasicBlock(self.0.llvm()) })
     }
 
+    /// Get the debug info subprogram attached to the function
+    pub fn subprogram(self) -> Result<Metadata<'a>, Error> {
+        let md = unsafe { llvm::debuginfo::LLVMGetSubprogram(self.as_ref().llvm()) };
+        Metadata::from_raw(&self.0.into_context()?, md)
+    }
+
+    /// Attach a debug info subprogram, see `DebugInfoBuilder::function`
+    pub fn set_subprogram(&mut self, subprogram: Metadata<'a>) {
+        unsafe { llvm::debuginfo::LLVMSetSubprogram(self.as_ref().llvm(), subprogram.raw()) }
+    }
+
     /// Append a new block
     pub fn append_basic_block(self, bb: BasicBlock<'a>) {
         unsafe { llvm::core::LLVMAppendExistingBasicBlock(self.0.llvm(), bb.llvm()) }
     }
 }
+
+/// Global alias
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GlobalAlias<'a>(pub Value<'a>);
+
+impl<'a> AsRef<Value<'a>> for GlobalAlias<'a> {
+    fn as_ref(&self) -> &Value<'a> {
+        &self.0
+    }
+}
+
+impl<'a> From<GlobalAlias<'a>> for Value<'a> {
+    fn from(x: GlobalAlias<'a>) -> Value<'a> {
+        x.0
+    }
+}
+
+impl<'a> GlobalAlias<'a> {
+    /// Get the name of the alias
+    pub fn name(self) -> Result<&'a str, Error> {
+        self.0.name()
+    }
+
+    /// Get the type of the aliased value
+    pub fn value_type(self) -> Result<Type<'a>, Error> {
+        unsafe { Type::from_inner(llvm::core::LLVMGlobalGetValueType(self.as_ref().llvm())) }
+    }
+
+    /// Get the aliased value
+    pub fn aliasee(self) -> Result<Value<'a>, Error> {
+        unsafe { Value::from_inner(llvm::core::LLVMAliasGetAliasee(self.as_ref().llvm())) }
+    }
+
+    /// Set the aliased value
+    pub fn set_aliasee(&mut self, aliasee: impl AsRef<Value<'a>>) {
+        unsafe { llvm::core::LLVMAliasSetAliasee(self.as_ref().llvm(), aliasee.as_ref().llvm()) }
+    }
+
+    /// Get the next alias
+    pub fn next_alias(self) -> Result<GlobalAlias<'a>, Error> {
+        let v = unsafe { llvm::core::LLVMGetNextGlobalAlias(self.as_ref().llvm()) };
+        Value::from_inner(v).map(GlobalAlias)
+    }
+}
+
+/// Global indirect function, the address is selected at load time by calling the resolver
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GlobalIFunc<'a>(pub Value<'a>);
+
+impl<'a> AsRef<Value<'a>> for GlobalIFunc<'a> {
+    fn as_ref(&self) -> &Value<'a> {
+        &self.0
+    }
+}
+
+impl<'a> From<GlobalIFunc<'a>> for Value<'a> {
+    fn from(x: GlobalIFunc<'a>) -> Value<'a> {
+        x.0
+    }
+}
+
+impl<'a> GlobalIFunc<'a> {
+    /// Get the name of the ifunc
+    pub fn name(self) -> Result<&'a str, Error> {
+        self.0.name()
+    }
+
+    /// Get the `FuncType`
+    pub fn func_type(self) -> Result<FuncType<'a>, Error> {
+        unsafe { Type::from_inner(llvm::core::LLVMGlobalGetValueType(self.as_ref().llvm()))? }
+            .to_func_type()
+    }
+
+    /// Get the resolver function
+    pub fn resolver(self) -> Result<Func<'a>, Error> {
+        unsafe {
+            Value::from_inner(llvm::core::LLVMGetGlobalIFuncResolver(self.as_ref().llvm()))
+                .map(Func)
+        }
+    }
+
+    /// Set the resolver function
+    pub fn set_resolver(&mut self, resolver: Func<'a>) {
+        unsafe {
+            llvm::core::LLVMSetGlobalIFuncResolver(self.as_ref().llvm(), resolver.as_ref().llvm())
+        }
+    }
+
+    /// Get the next ifunc
+    pub fn next_ifunc(self) -> Result<GlobalIFunc<'a>, Error> {
+        let v = unsafe { llvm::core::LLVMGetNextGlobalIFunc(self.as_ref().llvm()) };
+        Value::from_inner(v).map(GlobalIFunc)
+    }
+
+    /// Delete an ifunc
+    pub fn delete(self) {
+        unsafe { llvm::core::LLVMEraseGlobalIFunc(self.as_ref().llvm()) }
+    }
+}